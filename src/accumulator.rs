@@ -0,0 +1,99 @@
+//! Mixing streams of colors one at a time, like the pixels of a scanline or
+//! the readings of a sensor, without collecting them into a slice first.
+
+use crate::mixer::Mixer;
+use crate::space::ColorSpace;
+use crate::ComputeError;
+use css_colors::RGB;
+use std::marker::PhantomData;
+
+/// Colors pushed one at a time and mixed into one by `finish`, see
+/// [`Mixer::accumulator`].
+pub trait Accumulator {
+    /// Adds `color` with `weight`.
+    fn push_weighted(&mut self, color: RGB, weight: f32);
+
+    /// Adds `color` with a weight of 1.0.
+    fn push(&mut self, color: RGB) {
+        self.push_weighted(color, 1.0)
+    }
+
+    /// Mixes everything pushed so far, with the result and errors of
+    /// [`Mixer::mix_weighted`] on the same colors.
+    fn finish(self: Box<Self>) -> Result<RGB, ComputeError>;
+}
+
+/// Collects the colors and mixes them all in `finish`, for mixers that need
+/// every input at once, like the median.
+pub struct Buffered<'a, M: Mixer + ?Sized> {
+    mixer: &'a M,
+    input: Vec<RGB>,
+    weights: Vec<f32>,
+}
+
+impl<'a, M: Mixer + ?Sized> Buffered<'a, M> {
+    pub fn new(mixer: &'a M) -> Self {
+        Buffered {
+            mixer,
+            input: Vec::new(),
+            weights: Vec::new(),
+        }
+    }
+}
+
+impl<M: Mixer + ?Sized> Accumulator for Buffered<'_, M> {
+    fn push_weighted(&mut self, color: RGB, weight: f32) {
+        self.input.push(color);
+        self.weights.push(weight);
+    }
+
+    fn finish(self: Box<Self>) -> Result<RGB, ComputeError> {
+        self.mixer.mix_weighted(&self.input, &self.weights)
+    }
+}
+
+/// Keeps a running weighted sum of the components in space `S`, in constant
+/// memory, for the mixers averaging in a [`ColorSpace`].
+pub struct Running<S: ColorSpace> {
+    sum: [f32; 3],
+    total: f32,
+    count: usize,
+    invalid: bool,
+    space: PhantomData<S>,
+}
+
+impl<S: ColorSpace> Running<S> {
+    pub fn new(_space: S) -> Self {
+        Running {
+            sum: [0.0; 3],
+            total: 0.0,
+            count: 0,
+            invalid: false,
+            space: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSpace> Accumulator for Running<S> {
+    fn push_weighted(&mut self, color: RGB, weight: f32) {
+        self.count += 1;
+        if !weight.is_finite() || weight < 0.0 {
+            self.invalid = true;
+            return;
+        }
+        for (s, c) in self.sum.iter_mut().zip(S::to_components(color)) {
+            *s += c * weight;
+        }
+        self.total += weight;
+    }
+
+    fn finish(self: Box<Self>) -> Result<RGB, ComputeError> {
+        if self.count == 0 {
+            return Err(ComputeError::EmptyInput);
+        }
+        if self.invalid || !(self.total > 0.0 && self.total.is_finite()) {
+            return Err(ComputeError::InvalidWeights);
+        }
+        Ok(S::from_components(self.sum.map(|s| s / self.total)))
+    }
+}
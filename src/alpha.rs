@@ -0,0 +1,41 @@
+//! Mixing translucent colors.
+//!
+//! Mixers work on opaque colors, alpha is mixed premultiplied around them:
+//! every input counts by its weight times its alpha, and the mix has the
+//! weighted mean alpha of the inputs. For the averaging mixers that is
+//! exactly averaging premultiplied colors and dividing by the mean alpha,
+//! the other mixers get the same shares. A transparent input does not tint
+//! the mix at all, it only makes it more transparent.
+
+use crate::ComputeError;
+
+/// Checks that there is an alpha in `0.0..=1.0` for every one of `len`
+/// inputs.
+pub fn check_alphas(len: usize, alphas: &[f32]) -> Result<(), ComputeError> {
+    if alphas.len() != len || alphas.iter().any(|a| !(0.0..=1.0).contains(a)) {
+        return Err(ComputeError::AlphaOutOfRange);
+    }
+    Ok(())
+}
+
+/// The alpha of the mix, the mean of `alphas` weighted by `weights`. Zero if
+/// the total weight is not positive.
+pub fn mixed_alpha(weights: &[f32], alphas: &[f32]) -> f32 {
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 || !total.is_finite() {
+        return 0.0;
+    }
+    let alpha: f32 = weights.iter().zip(alphas).map(|(w, a)| w * a).sum();
+    (alpha / total).clamp(0.0, 1.0)
+}
+
+/// The weights the opaque colors are mixed with, `weights` times `alphas`.
+/// `None` if every input is transparent, leaving no color to mix.
+pub fn premultiplied_weights(weights: &[f32], alphas: &[f32]) -> Option<Vec<f32>> {
+    let premultiplied: Vec<f32> = weights.iter().zip(alphas).map(|(w, a)| w * a).collect();
+    if premultiplied.iter().any(|w| *w > 0.0) {
+        Some(premultiplied)
+    } else {
+        None
+    }
+}
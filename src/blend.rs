@@ -0,0 +1,273 @@
+//! Two-color blending in a selectable color space.
+//!
+//! `--mix-space` only affects operations that are agnostic of the space they
+//! work in, currently the pairwise folds of `less_mix` and `progressive_mix`.
+//! `rgb_avg` always works on gamma encoded sRGB bytes and `hsl_geo` always
+//! works in HSL, since the space is what defines those strategies.
+
+use crate::convert::rgb_to_hsl;
+use crate::space::{blend_many_weighted, LinearSpace, OklabSpace};
+use css_colors::{Angle, Color, Ratio, HSL, RGB};
+use std::str::FromStr;
+
+/// The color space two colors are interpolated in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MixSpace {
+    /// Gamma encoded sRGB, using the `css_colors` (Sass) mix.
+    #[default]
+    Srgb,
+    /// Linear-light sRGB.
+    Linear,
+    /// OKLab.
+    Oklab,
+    /// HSL, interpolating hue along the shorter arc.
+    Hsl,
+}
+
+impl FromStr for MixSpace {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "srgb" => Ok(MixSpace::Srgb),
+            "linear" => Ok(MixSpace::Linear),
+            "oklab" => Ok(MixSpace::Oklab),
+            "hsl" => Ok(MixSpace::Hsl),
+            _ => Err(format!(
+                "unknown mix space `{}`, expected srgb, linear, oklab or hsl",
+                s
+            )),
+        }
+    }
+}
+
+/// Which way around the hue circle hues are interpolated, as in CSS Color 4
+/// `<hue-interpolation-method>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HuePath {
+    /// The arc of at most 180°.
+    #[default]
+    Shorter,
+    /// The arc of at least 180°.
+    Longer,
+    /// Counterclockwise, toward larger angles.
+    Increasing,
+    /// Clockwise, toward smaller angles.
+    Decreasing,
+}
+
+impl HuePath {
+    pub fn name(self) -> &'static str {
+        match self {
+            HuePath::Shorter => "shorter",
+            HuePath::Longer => "longer",
+            HuePath::Increasing => "increasing",
+            HuePath::Decreasing => "decreasing",
+        }
+    }
+}
+
+impl FromStr for HuePath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shorter" => Ok(HuePath::Shorter),
+            "longer" => Ok(HuePath::Longer),
+            "increasing" => Ok(HuePath::Increasing),
+            "decreasing" => Ok(HuePath::Decreasing),
+            _ => Err(format!(
+                "unknown hue path `{}`, expected shorter, longer, increasing or decreasing",
+                s
+            )),
+        }
+    }
+}
+
+/// How much the hue of every input counts in a circular hue mean, on top of
+/// its weight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HueVote {
+    /// Every hue counts alike, even that of a gray.
+    #[default]
+    Equal,
+    /// Hues count by HSL saturation, grays do not count.
+    Saturation,
+    /// Hues count by chroma, the spread of the sRGB channels, so neither
+    /// grays nor very light or dark colors count much.
+    Chroma,
+}
+
+impl HueVote {
+    pub fn name(self) -> &'static str {
+        match self {
+            HueVote::Equal => "equal",
+            HueVote::Saturation => "saturation",
+            HueVote::Chroma => "chroma",
+        }
+    }
+
+    /// The vote of a color in HSL, in `0.0..=1.0`.
+    pub fn of(self, color: &HSL) -> f32 {
+        let s = color.s.as_f32();
+        match self {
+            HueVote::Equal => 1.0,
+            HueVote::Saturation => s,
+            HueVote::Chroma => s * (1.0 - (2.0 * color.l.as_f32() - 1.0).abs()),
+        }
+    }
+}
+
+impl FromStr for HueVote {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "equal" => Ok(HueVote::Equal),
+            "saturation" => Ok(HueVote::Saturation),
+            "chroma" => Ok(HueVote::Chroma),
+            _ => Err(format!(
+                "unknown hue vote `{}`, expected equal, saturation or chroma",
+                s
+            )),
+        }
+    }
+}
+
+/// What a circular hue mean does with achromatic inputs, whose hue is
+/// undefined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Achromatic {
+    /// Grays vote for the hue of 0° they are converted with.
+    #[default]
+    Vote,
+    /// Grays do not vote. Without any other input, the mix is a gray.
+    Skip,
+    /// Grays do not vote. Without any other input, the mix is `rgb_avg`.
+    RgbAvg,
+    /// Grays do not vote. Without any other input, the mix fails.
+    Error,
+}
+
+impl FromStr for Achromatic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "vote" => Ok(Achromatic::Vote),
+            "skip" => Ok(Achromatic::Skip),
+            "rgb-avg" => Ok(Achromatic::RgbAvg),
+            "error" => Ok(Achromatic::Error),
+            _ => Err(format!(
+                "unknown achromatic policy `{}`, expected vote, skip, rgb-avg or error",
+                s
+            )),
+        }
+    }
+}
+
+/// Interpolates from hue `from` (at `t = 0.0`) to `to` (at `t = 1.0`), both
+/// in degrees, along `path`. The result is in `0.0..360.0`.
+pub fn interpolate_hue(from: f32, to: f32, t: f32, path: HuePath) -> f32 {
+    let (from, to) = (from.rem_euclid(360.0), to.rem_euclid(360.0));
+    let delta = to - from;
+    let delta = match path {
+        HuePath::Shorter if delta > 180.0 => delta - 360.0,
+        HuePath::Shorter if delta < -180.0 => delta + 360.0,
+        HuePath::Longer if 0.0 < delta && delta < 180.0 => delta - 360.0,
+        HuePath::Longer if -180.0 < delta && delta <= 0.0 => delta + 360.0,
+        HuePath::Increasing if delta < 0.0 => delta + 360.0,
+        HuePath::Decreasing if delta > 0.0 => delta - 360.0,
+        _ => delta,
+    };
+    (from + delta * t).rem_euclid(360.0)
+}
+
+/// Interpolates from `from` (at `t = 0.0`) to `to` (at `t = 1.0`).
+pub fn blend_two(from: RGB, to: RGB, t: f32, space: MixSpace) -> RGB {
+    let t = t.clamp(0.0, 1.0);
+    let lerp = |a: f32, b: f32| a + (b - a) * t;
+
+    match space {
+        MixSpace::Srgb => from.mix(to, Ratio::from_f32(1.0 - t)).to_rgb(),
+        MixSpace::Linear => {
+            blend_many_weighted(&[from, to], &[1.0 - t, t], LinearSpace).unwrap_or(from)
+        }
+        MixSpace::Oklab => {
+            blend_many_weighted(&[from, to], &[1.0 - t, t], OklabSpace).unwrap_or(from)
+        }
+        MixSpace::Hsl => {
+            let (a, b) = (rgb_to_hsl(from), rgb_to_hsl(to));
+            let h1 = f32::from(a.h.degrees());
+            let h2 = f32::from(b.h.degrees());
+            let delta = (h2 - h1 + 180.0).rem_euclid(360.0) - 180.0;
+            let hue = (h1 + delta * t).rem_euclid(360.0).round() as u16;
+            HSL {
+                h: Angle::new(hue % 360),
+                s: Ratio::from_f32(lerp(a.s.as_f32(), b.s.as_f32())),
+                l: Ratio::from_f32(lerp(a.l.as_f32(), b.l.as_f32())),
+            }
+            .to_rgb()
+        }
+    }
+}
+
+/// Material style overlay opacity for an elevation in dp. Elevations between
+/// the defined steps use the step below them, anything above 24dp uses the
+/// 24dp opacity.
+///
+/// | dp | 0 | 1 | 2 | 3 | 4 | 6 | 8 | 12 | 16 | 24 |
+/// |----|---|---|---|---|---|---|---|----|----|----|
+/// | %  | 0 | 5 | 7 | 8 | 9 | 11| 12| 14 | 15 | 16 |
+pub fn elevation_opacity(elevation: u8) -> f32 {
+    const STEPS: [(u8, f32); 10] = [
+        (0, 0.0),
+        (1, 0.05),
+        (2, 0.07),
+        (3, 0.08),
+        (4, 0.09),
+        (6, 0.11),
+        (8, 0.12),
+        (12, 0.14),
+        (16, 0.15),
+        (24, 0.16),
+    ];
+    STEPS
+        .iter()
+        .rev()
+        .find(|(dp, _)| *dp <= elevation)
+        .map(|(_, opacity)| *opacity)
+        .unwrap_or(0.0)
+}
+
+/// Mixes `color` toward the `surface` it sits on by the overlay opacity of
+/// `elevation`. Elevation 0 returns `color` unchanged.
+pub fn on_surface(color: RGB, surface: RGB, elevation: u8) -> RGB {
+    let opacity = elevation_opacity(elevation);
+    if opacity == 0.0 {
+        return color;
+    }
+    blend_two(color, surface, opacity, MixSpace::Srgb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use css_colors::rgb;
+
+    #[test]
+    fn black_and_white_blend_lighter_in_linear_light() {
+        let (black, white) = (rgb(0, 0, 0), rgb(255, 255, 255));
+        let srgb = blend_two(black, white, 0.5, MixSpace::Srgb);
+        let linear = blend_two(black, white, 0.5, MixSpace::Linear);
+        assert!((i16::from(srgb.r.as_u8()) - 128).abs() <= 1);
+        assert!(linear.r.as_u8() > 180);
+    }
+
+    #[test]
+    fn elevation_zero_leaves_the_color_unchanged() {
+        let (color, surface) = (rgb(200, 30, 60), rgb(255, 255, 255));
+        assert_eq!(on_surface(color, surface, 0), color);
+        assert_ne!(on_surface(color, surface, 4), color);
+    }
+}
@@ -0,0 +1,160 @@
+//! The separable blend modes of CSS Compositing and Blending Level 1, as
+//! used by `mix-blend-mode` and image editors.
+//!
+//! Blend modes work on gamma encoded sRGB channels in `0.0..=1.0`, each
+//! channel on its own: `backdrop` is the color below, `source` the color
+//! blended onto it.
+
+use css_colors::{Ratio, RGB};
+use std::str::FromStr;
+
+/// A separable blend mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// The source replaces the backdrop.
+    Normal,
+    /// Product of the channels, always darker, like stacked filters.
+    #[default]
+    Multiply,
+    /// Inverse of multiplying the inverses, always lighter, like projecting
+    /// both colors.
+    Screen,
+    /// Multiplies dark backdrops and screens light ones.
+    Overlay,
+    /// The darker channel.
+    Darken,
+    /// The lighter channel.
+    Lighten,
+    /// Brightens the backdrop toward the source.
+    ColorDodge,
+    /// Darkens the backdrop toward the source.
+    ColorBurn,
+    /// Overlay with backdrop and source swapped.
+    HardLight,
+    /// A softer hard light.
+    SoftLight,
+    /// Absolute difference of the channels.
+    Difference,
+    /// Difference with less contrast.
+    Exclusion,
+}
+
+/// Every blend mode, in the order of the specification.
+pub const BLEND_MODES: [BlendMode; 12] = [
+    BlendMode::Normal,
+    BlendMode::Multiply,
+    BlendMode::Screen,
+    BlendMode::Overlay,
+    BlendMode::Darken,
+    BlendMode::Lighten,
+    BlendMode::ColorDodge,
+    BlendMode::ColorBurn,
+    BlendMode::HardLight,
+    BlendMode::SoftLight,
+    BlendMode::Difference,
+    BlendMode::Exclusion,
+];
+
+impl BlendMode {
+    /// The CSS keyword of the mode.
+    pub fn name(self) -> &'static str {
+        match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::Darken => "darken",
+            BlendMode::Lighten => "lighten",
+            BlendMode::ColorDodge => "color-dodge",
+            BlendMode::ColorBurn => "color-burn",
+            BlendMode::HardLight => "hard-light",
+            BlendMode::SoftLight => "soft-light",
+            BlendMode::Difference => "difference",
+            BlendMode::Exclusion => "exclusion",
+        }
+    }
+
+    /// Blends one channel of `source` onto the same channel of `backdrop`.
+    pub fn blend_channel(self, backdrop: f32, source: f32) -> f32 {
+        let (b, s) = (backdrop, source);
+        match self {
+            BlendMode::Normal => s,
+            BlendMode::Multiply => b * s,
+            BlendMode::Screen => b + s - b * s,
+            BlendMode::Overlay => BlendMode::HardLight.blend_channel(s, b),
+            BlendMode::Darken => b.min(s),
+            BlendMode::Lighten => b.max(s),
+            BlendMode::ColorDodge => {
+                if b == 0.0 {
+                    0.0
+                } else if s >= 1.0 {
+                    1.0
+                } else {
+                    (b / (1.0 - s)).min(1.0)
+                }
+            }
+            BlendMode::ColorBurn => {
+                if b >= 1.0 {
+                    1.0
+                } else if s == 0.0 {
+                    0.0
+                } else {
+                    1.0 - ((1.0 - b) / s).min(1.0)
+                }
+            }
+            BlendMode::HardLight => {
+                if s <= 0.5 {
+                    BlendMode::Multiply.blend_channel(b, 2.0 * s)
+                } else {
+                    BlendMode::Screen.blend_channel(b, 2.0 * s - 1.0)
+                }
+            }
+            BlendMode::SoftLight => {
+                if s <= 0.5 {
+                    b - (1.0 - 2.0 * s) * b * (1.0 - b)
+                } else {
+                    let d = if b <= 0.25 {
+                        ((16.0 * b - 12.0) * b + 4.0) * b
+                    } else {
+                        b.sqrt()
+                    };
+                    b + (2.0 * s - 1.0) * (d - b)
+                }
+            }
+            BlendMode::Difference => (b - s).abs(),
+            BlendMode::Exclusion => b + s - 2.0 * b * s,
+        }
+    }
+
+    /// Blends `source` onto `backdrop`, rounding to the nearest byte.
+    pub fn blend(self, backdrop: RGB, source: RGB) -> RGB {
+        let channel = |b: Ratio, s: Ratio| {
+            let value = self.blend_channel(b.as_f32(), s.as_f32());
+            Ratio::from_u8((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+        };
+        RGB {
+            r: channel(backdrop.r, source.r),
+            g: channel(backdrop.g, source.g),
+            b: channel(backdrop.b, source.b),
+        }
+    }
+}
+
+impl FromStr for BlendMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BLEND_MODES
+            .iter()
+            .find(|mode| mode.name() == s)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = BLEND_MODES.iter().map(|m| m.name()).collect();
+                format!(
+                    "unknown blend mode `{}`, expected one of {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
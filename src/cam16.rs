@@ -0,0 +1,234 @@
+//! The CAM16 color appearance model and its uniform color space CAM16-UCS,
+//! after Li et al., "Comprehensive color solutions: CAM16, CAT16, and
+//! CAM16-UCS" (2017).
+//!
+//! Appearance depends on how a color is viewed, so every conversion takes
+//! [`ViewingConditions`]. The model is evaluated in `f64`, its powers lose
+//! too much precision in `f32` to round trip sRGB bytes.
+
+use crate::convert::{rgb_to_xyz, xyz_to_rgb};
+use css_colors::RGB;
+use std::str::FromStr;
+
+/// CAT16 matrix from CIE XYZ to the sharpened cone responses.
+const M16: [[f64; 3]; 3] = [
+    [0.401_288, 0.650_173, -0.051_461],
+    [-0.250_268, 1.204_414, 0.045_854],
+    [-0.002_079, 0.048_952, 0.953_127],
+];
+
+/// Inverse of `M16`.
+const M16_INV: [[f64; 3]; 3] = [
+    [1.862_067_86, -1.011_254_63, 0.149_186_77],
+    [0.387_526_54, 0.621_447_44, -0.008_973_98],
+    [-0.015_841_5, -0.034_122_94, 1.049_964_44],
+];
+
+/// D65 reference white in CIE XYZ, scaled so that `Y = 100.0`.
+const WHITE: [f64; 3] = [95.047, 100.0, 108.883];
+
+/// The luminance of the area around the viewed stimulus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Surround {
+    /// Viewing surface colors, like a print in a lit room.
+    #[default]
+    Average,
+    /// Viewing a screen in a dim room.
+    Dim,
+    /// Viewing a projection in the dark.
+    Dark,
+}
+
+impl Surround {
+    /// The factor of the degree of adaptation `F`, the impact of the
+    /// surround `c` and the chromatic induction factor `N_c`.
+    fn factors(self) -> (f64, f64, f64) {
+        match self {
+            Surround::Average => (1.0, 0.69, 1.0),
+            Surround::Dim => (0.9, 0.59, 0.9),
+            Surround::Dark => (0.8, 0.525, 0.8),
+        }
+    }
+}
+
+impl FromStr for Surround {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "average" => Ok(Surround::Average),
+            "dim" => Ok(Surround::Dim),
+            "dark" => Ok(Surround::Dark),
+            _ => Err(format!(
+                "unknown surround `{}`, expected average, dim or dark",
+                s
+            )),
+        }
+    }
+}
+
+/// How the colors are viewed. The white point is always D65, the white of
+/// sRGB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewingConditions {
+    /// Luminance of the adapting field `L_A` in cd/m².
+    pub adapting_luminance: f32,
+    /// Relative luminance of the background `Y_b`, in `0.0..=100.0`.
+    pub background_luminance: f32,
+    pub surround: Surround,
+}
+
+impl Default for ViewingConditions {
+    /// The sRGB viewing conditions: a 64 lx environment reflected by a 20%
+    /// gray background.
+    fn default() -> Self {
+        ViewingConditions {
+            adapting_luminance: (64.0 / std::f64::consts::PI * 0.2) as f32,
+            background_luminance: 20.0,
+            surround: Surround::Average,
+        }
+    }
+}
+
+/// The values of the model that only depend on the viewing conditions.
+struct Model {
+    c: f64,
+    nc: f64,
+    n: f64,
+    z: f64,
+    nbb: f64,
+    fl: f64,
+    d_rgb: [f64; 3],
+    aw: f64,
+}
+
+fn mul(m: &[[f64; 3]; 3], v: [f64; 3]) -> [f64; 3] {
+    [0, 1, 2].map(|i| m[i][0] * v[0] + m[i][1] * v[1] + m[i][2] * v[2])
+}
+
+impl Model {
+    fn new(conditions: &ViewingConditions) -> Model {
+        let (f, c, nc) = conditions.surround.factors();
+        let la = f64::from(conditions.adapting_luminance).max(0.0);
+        let yb = f64::from(conditions.background_luminance).max(1e-3);
+
+        let rgb_w = mul(&M16, WHITE);
+        let d = (f * (1.0 - (1.0 / 3.6) * ((-la - 42.0) / 92.0).exp())).clamp(0.0, 1.0);
+        let d_rgb = rgb_w.map(|w| d * WHITE[1] / w + 1.0 - d);
+
+        let k = 1.0 / (5.0 * la + 1.0);
+        let k4 = k.powi(4);
+        let fl = 0.2 * k4 * (5.0 * la) + 0.1 * (1.0 - k4).powi(2) * (5.0 * la).cbrt();
+
+        let n = yb / WHITE[1];
+        let z = 1.48 + n.sqrt();
+        let nbb = 0.725 * n.powf(-0.2);
+
+        let mut model = Model {
+            c,
+            nc,
+            n,
+            z,
+            nbb,
+            fl,
+            d_rgb,
+            aw: 0.0,
+        };
+        let [r, g, b] = model.adapt(rgb_w);
+        model.aw = (2.0 * r + g + 0.05 * b - 0.305) * nbb;
+        model
+    }
+
+    /// Chromatic adaptation and the post-adaptation compression of the cone
+    /// responses to XYZ.
+    fn adapt(&self, rgb: [f64; 3]) -> [f64; 3] {
+        [0, 1, 2].map(|i| {
+            let x = self.d_rgb[i] * rgb[i];
+            let f = (self.fl * x.abs() / 100.0).powf(0.42);
+            x.signum() * 400.0 * f / (f + 27.13) + 0.1
+        })
+    }
+
+    /// Inverse of `adapt`.
+    fn unadapt(&self, rgb_a: [f64; 3]) -> [f64; 3] {
+        [0, 1, 2].map(|i| {
+            let x = rgb_a[i] - 0.1;
+            let base = (27.13 * x.abs() / (400.0 - x.abs())).max(0.0);
+            x.signum() * 100.0 / self.fl * base.powf(1.0 / 0.42) / self.d_rgb[i]
+        })
+    }
+
+    /// `(1.64 - 0.29^n)^0.73`, relating chroma and the correlate `t`.
+    fn chroma_factor(&self) -> f64 {
+        (1.64 - 0.29f64.powf(self.n)).powf(0.73)
+    }
+
+    fn eccentricity(h: f64) -> f64 {
+        0.25 * ((h.to_radians() + 2.0).cos() + 3.8)
+    }
+
+    /// Lightness `J`, colorfulness `M` and hue angle `h` in degrees of a
+    /// color in CIE XYZ with `Y = 100.0` for white.
+    fn jmh(&self, xyz: [f64; 3]) -> [f64; 3] {
+        let [r, g, b] = self.adapt(mul(&M16, xyz));
+        let a = r - 12.0 * g / 11.0 + b / 11.0;
+        let bb = (r + g - 2.0 * b) / 9.0;
+        let h = bb.atan2(a).to_degrees().rem_euclid(360.0);
+
+        let achromatic = (2.0 * r + g + 0.05 * b - 0.305) * self.nbb;
+        let j = 100.0 * (achromatic / self.aw).max(0.0).powf(self.c * self.z);
+
+        let denominator = r + g + 21.0 / 20.0 * b;
+        let t = if denominator > 0.0 {
+            50_000.0 / 13.0 * self.nc * self.nbb * Self::eccentricity(h) * a.hypot(bb) / denominator
+        } else {
+            0.0
+        };
+        let chroma = t.powf(0.9) * (j / 100.0).sqrt() * self.chroma_factor();
+        [j, chroma * self.fl.powf(0.25), h]
+    }
+
+    /// Inverse of `jmh`.
+    fn xyz(&self, [j, m, h]: [f64; 3]) -> [f64; 3] {
+        if j <= 0.0 {
+            return [0.0; 3];
+        }
+        let chroma = m / self.fl.powf(0.25);
+        let t = (chroma / ((j / 100.0).sqrt() * self.chroma_factor())).powf(1.0 / 0.9);
+        let p1 = 50_000.0 / 13.0 * self.nc * self.nbb * Self::eccentricity(h);
+        let p2 = self.aw * (j / 100.0).powf(1.0 / (self.c * self.z)) / self.nbb + 0.305;
+
+        // Solving the definition of `t` for the length `gamma` of (a, b).
+        let (sin, cos) = h.to_radians().sin_cos();
+        let gamma = t * p2 / (p1 + t * (671.0 * cos + 6588.0 * sin) / 1403.0);
+        let (a, b) = (gamma * cos, gamma * sin);
+
+        let rgb_a = [
+            (460.0 * p2 + 451.0 * a + 288.0 * b) / 1403.0,
+            (460.0 * p2 - 891.0 * a - 261.0 * b) / 1403.0,
+            (460.0 * p2 - 220.0 * a - 6300.0 * b) / 1403.0,
+        ];
+        mul(&M16_INV, self.unadapt(rgb_a))
+    }
+}
+
+/// Converts a color to CAM16-UCS `[J', a', b']` under `conditions`.
+pub fn rgb_to_ucs(color: RGB, conditions: &ViewingConditions) -> [f32; 3] {
+    let model = Model::new(conditions);
+    let [j, m, h] = model.jmh(rgb_to_xyz(color).map(|v| f64::from(v) * 100.0));
+    let j_ucs = 1.7 * j / (1.0 + 0.007 * j);
+    let m_ucs = (1.0 + 0.0228 * m).ln() / 0.0228;
+    let (sin, cos) = h.to_radians().sin_cos();
+    [j_ucs, m_ucs * cos, m_ucs * sin].map(|v| v as f32)
+}
+
+/// Converts CAM16-UCS `[J', a', b']` under `conditions` back to sRGB,
+/// clamping out of gamut results.
+pub fn ucs_to_rgb(ucs: [f32; 3], conditions: &ViewingConditions) -> RGB {
+    let model = Model::new(conditions);
+    let [j_ucs, a, b] = ucs.map(f64::from);
+    let j = j_ucs / (1.7 - 0.007 * j_ucs);
+    let m = ((0.0228 * a.hypot(b)).exp() - 1.0) / 0.0228;
+    let h = b.atan2(a).to_degrees().rem_euclid(360.0);
+    xyz_to_rgb(model.xyz([j, m, h]).map(|v| (v / 100.0) as f32))
+}
@@ -0,0 +1,56 @@
+//! OKLCH chroma of mixes and moving a mix back out to the chroma of its
+//! inputs.
+
+use crate::convert::Oklch;
+use crate::convert::{oklab_in_gamut, oklab_to_oklch, oklab_to_rgb, oklch_to_oklab, rgb_to_oklab};
+use css_colors::RGB;
+
+/// Below this OKLCH chroma a color is treated as gray, its hue is noise.
+const GRAY_CHROMA: f32 = 1e-4;
+
+/// OKLCH chroma of `color`.
+pub fn chroma(color: RGB) -> f32 {
+    oklab_to_oklch(rgb_to_oklab(color)).c
+}
+
+/// The mean OKLCH chroma of `input` weighted by `weights`. `None` without
+/// inputs or if the total weight is not positive.
+pub fn mean_chroma(input: &[RGB], weights: &[f32]) -> Option<f32> {
+    let total: f32 = weights.iter().sum();
+    if input.is_empty() || total <= 0.0 {
+        return None;
+    }
+    let sum: f32 = input.iter().zip(weights).map(|(c, w)| chroma(*c) * w).sum();
+    Some(sum / total)
+}
+
+/// Whether OKLCH `color` is inside of the sRGB gamut.
+fn in_gamut(color: Oklch) -> bool {
+    oklab_in_gamut(oklch_to_oklab(color))
+}
+
+/// Changes the OKLCH chroma of `color` to `target`, keeping lightness and
+/// hue. Where `target` is outside of the sRGB gamut the chroma stops at the
+/// gamut boundary. Grays have no hue to move along and are kept.
+pub fn with_chroma(color: RGB, target: f32) -> RGB {
+    let Oklch { l, c, h } = oklab_to_oklch(rgb_to_oklab(color));
+    if c < GRAY_CHROMA || target <= 0.0 {
+        return color;
+    }
+    let at = |c: f32| Oklch { l, c, h };
+    let c = if in_gamut(at(target)) {
+        target
+    } else {
+        let (mut inside, mut outside) = (c.min(target), target);
+        for _ in 0..24 {
+            let mid = (inside + outside) / 2.0;
+            if in_gamut(at(mid)) {
+                inside = mid;
+            } else {
+                outside = mid;
+            }
+        }
+        inside
+    };
+    oklab_to_rgb(oklch_to_oklab(at(c)))
+}
@@ -0,0 +1,238 @@
+//! An SVG chromaticity diagram of the inputs and mix results.
+
+use crate::convert::{rgb_to_xy, WHITE_D65_XY};
+use crate::Record;
+use css_colors::{Color, RGB};
+use std::fmt::Write;
+
+/// The CIE 1931 2° spectral locus from 380nm to 700nm in 10nm steps.
+const SPECTRAL_LOCUS: [[f32; 2]; 33] = [
+    [0.1741, 0.0050],
+    [0.1738, 0.0049],
+    [0.1733, 0.0048],
+    [0.1726, 0.0048],
+    [0.1714, 0.0051],
+    [0.1689, 0.0069],
+    [0.1644, 0.0109],
+    [0.1566, 0.0177],
+    [0.1440, 0.0297],
+    [0.1241, 0.0578],
+    [0.0913, 0.1327],
+    [0.0454, 0.2950],
+    [0.0082, 0.5384],
+    [0.0139, 0.7502],
+    [0.0743, 0.8338],
+    [0.1547, 0.8059],
+    [0.2296, 0.7543],
+    [0.3016, 0.6923],
+    [0.3731, 0.6245],
+    [0.4441, 0.5547],
+    [0.5125, 0.4866],
+    [0.5752, 0.4242],
+    [0.6270, 0.3725],
+    [0.6658, 0.3340],
+    [0.6915, 0.3083],
+    [0.7079, 0.2920],
+    [0.7190, 0.2809],
+    [0.7260, 0.2740],
+    [0.7300, 0.2700],
+    [0.7320, 0.2680],
+    [0.7334, 0.2666],
+    [0.7344, 0.2656],
+    [0.7347, 0.2653],
+];
+
+/// The sRGB red, green and blue primaries.
+const SRGB_PRIMARIES: [[f32; 2]; 3] = [[0.64, 0.33], [0.30, 0.60], [0.15, 0.06]];
+
+/// Pixels per unit of chromaticity.
+const SCALE: f32 = 500.0;
+const WIDTH: f32 = 0.8 * SCALE;
+const HEIGHT: f32 = 0.9 * SCALE;
+
+fn to_svg_point([x, y]: [f32; 2]) -> (f32, f32) {
+    (x * SCALE, HEIGHT - y * SCALE)
+}
+
+fn points(xy: &[[f32; 2]]) -> String {
+    xy.iter()
+        .map(|p| {
+            let (x, y) = to_svg_point(*p);
+            format!("{:.1},{:.1}", x, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn circle(out: &mut String, class: &str, color: RGB, radius: f32) {
+    let (x, y) = to_svg_point(rgb_to_xy(color));
+    writeln!(
+        out,
+        "    <circle class='{}' cx='{:.1}' cy='{:.1}' r='{}' fill='{}' stroke='#333' stroke-width='0.5'/>",
+        class,
+        x,
+        y,
+        radius,
+        color.to_css()
+    )
+    .unwrap();
+}
+
+/// Renders every record's inputs and outputs into a CIE 1931 xy diagram,
+/// together with the spectral locus, the sRGB gamut and the D65 white point.
+/// Each record also gets a cross at the centroid of its inputs.
+pub fn to_svg(records: &[Record]) -> String {
+    let mut out = svg_header();
+    for record in records {
+        out.push_str(&svg_record(record));
+    }
+    out.push_str(SVG_FOOTER);
+    out
+}
+
+/// Closes the document started by `svg_header`.
+pub const SVG_FOOTER: &str = "</svg>\n";
+
+/// The start of the diagram of `to_svg`, up to the first record.
+pub fn svg_header() -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "<svg xmlns='http://www.w3.org/2000/svg' width='{w}' height='{h}' viewBox='0 0 {w} {h}'>",
+        w = WIDTH,
+        h = HEIGHT
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "<polygon class='locus' points='{}' fill='none' stroke='black'/>",
+        points(&SPECTRAL_LOCUS)
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "<polygon class='srgb-gamut' points='{}' fill='none' stroke='gray' stroke-dasharray='4'/>",
+        points(&SRGB_PRIMARIES)
+    )
+    .unwrap();
+    let (wx, wy) = to_svg_point(WHITE_D65_XY);
+    writeln!(
+        out,
+        "<circle class='white-point' cx='{:.1}' cy='{:.1}' r='2' fill='black'/>\n<text x='{:.1}' y='{:.1}' font-size='10'>D65</text>",
+        wx,
+        wy,
+        wx + 4.0,
+        wy - 4.0
+    )
+    .unwrap();
+    out
+}
+
+/// The group of one record in the diagram of `to_svg`.
+pub fn svg_record(record: &Record) -> String {
+    let mut out = String::new();
+    writeln!(out, "<g class='record record-{}'>", record.id).unwrap();
+    for input in &record.input {
+        circle(&mut out, "input", *input, 3.0);
+    }
+    for (slug, output) in record.outputs.iter() {
+        circle(&mut out, &format!("output {}", slug), *output, 5.0);
+    }
+
+    let n = record.input.len() as f32;
+    let [cx, cy] = record
+        .input
+        .iter()
+        .map(|c| rgb_to_xy(*c))
+        .fold([0.0, 0.0], |[sx, sy], [x, y]| [sx + x, sy + y]);
+    let (cx, cy) = to_svg_point([cx / n, cy / n]);
+    writeln!(
+        out,
+        "    <path class='centroid' d='M{:.1},{:.1}h8M{:.1},{:.1}v8' stroke='black'/>",
+        cx - 4.0,
+        cy,
+        cx,
+        cy - 4.0
+    )
+    .unwrap();
+    writeln!(out, "</g>").unwrap();
+    out
+}
+
+fn cross(o: [f32; 2], a: [f32; 2], b: [f32; 2]) -> f32 {
+    (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+}
+
+/// One half of a monotone chain hull over points sorted by x.
+fn half_hull<'a>(points: impl Iterator<Item = &'a [f32; 2]>) -> Vec<[f32; 2]> {
+    let mut hull: Vec<[f32; 2]> = Vec::new();
+    for p in points {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], *p) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(*p);
+    }
+    hull
+}
+
+/// Area of the convex hull of `points`, using Andrew's monotone chain.
+fn hull_area(points: &[[f32; 2]]) -> f32 {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return 0.0;
+    }
+
+    let mut hull = half_hull(sorted.iter());
+    hull.pop();
+    let mut upper = half_hull(sorted.iter().rev());
+    upper.pop();
+    hull.extend(upper);
+
+    let area: f32 = (0..hull.len())
+        .map(|i| {
+            let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+            a[0] * b[1] - b[0] * a[1]
+        })
+        .sum();
+    area.abs() / 2.0
+}
+
+/// Fraction of the sRGB gamut's xy chromaticity triangle covered by the
+/// convex hull of `colors`, in `0.0..=1.0`.
+pub fn gamut_coverage(colors: &[RGB]) -> f32 {
+    let points: Vec<[f32; 2]> = colors.iter().map(|c| rgb_to_xy(*c)).collect();
+    hull_area(&points) / hull_area(&SRGB_PRIMARIES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, mixer, Options};
+    use css_colors::rgb;
+
+    #[test]
+    fn svg_plots_every_input_and_output() {
+        let input = vec![rgb(255, 0, 0), rgb(0, 255, 0), rgb(0, 0, 255)];
+        let record = generate::compute_record(
+            "test".to_string(),
+            input,
+            &mixer::registry(&Options::default()),
+        );
+        let svg = to_svg(std::slice::from_ref(&record));
+        assert_eq!(svg.matches("<circle class='input'").count(), 3);
+        assert_eq!(
+            svg.matches("<circle class='output ").count(),
+            record.outputs.len()
+        );
+    }
+
+    #[test]
+    fn primaries_cover_more_of_the_gamut_than_similar_colors() {
+        let primaries = [rgb(255, 0, 0), rgb(0, 255, 0), rgb(0, 0, 255)];
+        let similar = [rgb(120, 80, 60), rgb(122, 80, 60), rgb(120, 82, 61)];
+        assert!((gamut_coverage(&primaries) - 1.0).abs() < 1e-3);
+        assert!(gamut_coverage(&similar) < 0.01);
+    }
+}
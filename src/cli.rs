@@ -0,0 +1,779 @@
+//! The command line interface, turning arguments into `Options`.
+
+use clap::{Args, Parser, Subcommand};
+use color_mix::blend::{Achromatic, HuePath, HueVote, MixSpace};
+use color_mix::blend_mode::BlendMode;
+use color_mix::cam16::Surround;
+use color_mix::command_mixer::CommandMixer;
+use color_mix::css_mix::CssMixSpace;
+use color_mix::cvd::Cvd;
+use color_mix::easing::Easing;
+use color_mix::generate::{Preset, Sampling};
+use color_mix::input;
+use color_mix::options::{parse_color, parse_weighted_hex, WeightedColor};
+use color_mix::options::{CssColorFormat, DeltaBaseline, PlaceholderStyle};
+use color_mix::quantize::Palette;
+use color_mix::Options;
+use color_mix::{mixer, run};
+use css_colors::RGB;
+use std::io;
+use std::path::PathBuf;
+
+/// Compare strategies for mixing several colors into one.
+///
+/// Without a subcommand, `generate` is run.
+#[derive(Parser)]
+#[command(version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    /// Configuration file, `color-mix.toml` in the working directory is
+    /// used if it exists. Flags override its values.
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub generate: GenerateArgs,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Mix random palettes and render the comparison page.
+    Generate(Box<GenerateArgs>),
+    /// Mix the given colors and print the result of every mixer.
+    Mix(MixArgs),
+    /// Print a color in every color space color-mix knows.
+    Convert(ConvertArgs),
+    /// Render a run saved with `generate --save-json` again.
+    Render(RenderCommandArgs),
+    /// Extract the most representative colors of an image and mix them.
+    #[cfg(feature = "image-input")]
+    Extract(ExtractArgs),
+}
+
+/// Flags configuring the mixers.
+#[derive(Args)]
+pub struct MixingArgs {
+    /// Only run these mixers, in registry order, e.g.
+    /// rgb-avg,oklab-avg,hsl-geo. All of them if missing.
+    #[arg(long, value_delimiter = ',', value_parser = parse_method)]
+    pub methods: Vec<String>,
+    /// Space the pairwise blends of less-mix and progressive-mix happen in:
+    /// srgb, linear, oklab or hsl.
+    #[arg(long)]
+    pub mix_space: Option<MixSpace>,
+    /// Hue arc of oklch-avg and of css-color-mix in polar spaces: shorter,
+    /// longer, increasing or decreasing.
+    #[arg(long)]
+    pub hue_path: Option<HuePath>,
+    /// Luminance of the adapting field of cam16-avg in cd/m², 4.07 for sRGB.
+    #[arg(long)]
+    pub cam16_adapting_luminance: Option<f32>,
+    /// Relative luminance of the background of cam16-avg, 0 to 100.
+    #[arg(long)]
+    pub cam16_background_luminance: Option<f32>,
+    /// Surround of cam16-avg: average, dim or dark.
+    #[arg(long)]
+    pub cam16_surround: Option<Surround>,
+    /// Fraction of the inputs trimmed-mean discards from both ends of every
+    /// channel, at least 0 and below 0.5.
+    #[arg(long, value_parser = parse_trim_fraction)]
+    pub trim_fraction: Option<f32>,
+    /// Number of clusters kmeans splits the inputs into, at least 1.
+    #[arg(long, value_parser = parse_k)]
+    pub kmeans_k: Option<usize>,
+    /// Collapse inputs closer than this CIEDE2000 distance to an earlier
+    /// input into it before mixing, e.g. 2, logging every merge.
+    #[arg(long, value_parser = parse_delta_e)]
+    pub dedup: Option<f32>,
+    /// Skip colors and palettes of the input that cannot be parsed with a
+    /// warning instead of failing.
+    #[arg(long)]
+    pub lenient: bool,
+    /// Scale additive-light sums brighter than white down until they fit,
+    /// keeping their hue, instead of clipping every channel.
+    #[arg(long)]
+    pub normalize_light: bool,
+    /// Opacity from 0 to 1 of every layer over composites onto the first
+    /// input, e.g. 0.5,0.25. The last one repeats for further layers.
+    #[arg(long, value_delimiter = ',', value_parser = parse_opacity)]
+    pub layer_opacity: Vec<f32>,
+    /// Blend mode of blend-mode, like multiply, screen or overlay.
+    #[arg(long)]
+    pub blend: Option<BlendMode>,
+    /// Space css-color-mix interpolates in, as in color-mix(in oklab, ...).
+    #[arg(long)]
+    pub css_mix_space: Option<CssMixSpace>,
+    /// Mix hues of inputs moved to a common luminance.
+    #[arg(long)]
+    pub normalize_luminance: bool,
+    /// Sweep two-color mixes from the first color to the second in this
+    /// many steps, at least 2, and show every mixer as a strip.
+    #[arg(long, value_parser = parse_steps)]
+    pub strip: Option<usize>,
+    /// How the share of the second color grows along --strip: linear,
+    /// ease-in-out or cubic.
+    #[arg(long)]
+    pub easing: Option<Easing>,
+    /// Mix the inputs of every mixer pairwise in a balanced tree instead
+    /// of all at once.
+    #[arg(long)]
+    pub tournament: bool,
+    /// Move the result of every mixer to the mean luminance of its inputs,
+    /// keeping its hue and saturation.
+    #[arg(long)]
+    pub preserve_brightness: bool,
+    /// White balance the result of every mixer to the mean correlated color
+    /// temperature of its inputs.
+    #[arg(long)]
+    pub match_temperature: bool,
+    /// Move the result of every mixer out to the mean OKLCH chroma of its
+    /// inputs, as far as the sRGB gamut allows.
+    #[arg(long)]
+    pub preserve_chroma: bool,
+    /// What the hue of an input counts for in hsl-geo: equal, saturation or
+    /// chroma.
+    #[arg(long)]
+    pub hue_vote: Option<HueVote>,
+    /// What hsl-geo does with grays: vote for 0°, skip them and mix grays
+    /// to a gray, skip them and fall back to rgb-avg, or skip them and fail.
+    #[arg(long)]
+    pub achromatic: Option<Achromatic>,
+    /// Exit with an error report if any mixer fails, instead of falling
+    /// back to black.
+    #[arg(long)]
+    pub strict: bool,
+    /// Run a program as a mixer named name, e.g. spline=./spline.py. It
+    /// reads the inputs as JSON on stdin and prints the mix as hex on
+    /// stdout. Can be repeated.
+    #[arg(long)]
+    pub mixer_cmd: Vec<CommandMixer>,
+    /// Directory of .wasm mixers to run after the built-in ones, see the
+    /// `wasm_plugin` module for their interface.
+    #[cfg(feature = "wasm-plugins")]
+    #[arg(long)]
+    pub plugin_dir: Option<PathBuf>,
+}
+
+impl MixingArgs {
+    fn apply(&self, options: &mut Options) {
+        if !self.methods.is_empty() {
+            options.mixers = Some(self.methods.clone());
+        }
+        if let Some(mix_space) = self.mix_space {
+            options.mix_space = mix_space;
+        }
+        if let Some(hue_path) = self.hue_path {
+            options.hue_path = hue_path;
+        }
+        if let Some(luminance) = self.cam16_adapting_luminance {
+            options.cam16.adapting_luminance = luminance;
+        }
+        if let Some(luminance) = self.cam16_background_luminance {
+            options.cam16.background_luminance = luminance;
+        }
+        if let Some(surround) = self.cam16_surround {
+            options.cam16.surround = surround;
+        }
+        if let Some(trim) = self.trim_fraction {
+            options.trim_fraction = trim;
+        }
+        if let Some(k) = self.kmeans_k {
+            options.kmeans_k = k;
+        }
+        if self.dedup.is_some() {
+            options.dedup = self.dedup;
+        }
+        options.lenient |= self.lenient;
+        options.normalize_light |= self.normalize_light;
+        if !self.layer_opacity.is_empty() {
+            options.layer_opacities = self.layer_opacity.clone();
+        }
+        if let Some(mode) = self.blend {
+            options.blend_mode = mode;
+        }
+        if let Some(space) = self.css_mix_space {
+            options.css_mix_space = space;
+        }
+        options.normalize_luminance |= self.normalize_luminance;
+        if self.strip.is_some() {
+            options.strip_steps = self.strip;
+        }
+        if let Some(easing) = self.easing {
+            options.easing = easing;
+        }
+        options.tournament |= self.tournament;
+        options.preserve_brightness |= self.preserve_brightness;
+        options.match_temperature |= self.match_temperature;
+        options.preserve_chroma |= self.preserve_chroma;
+        if let Some(hue_vote) = self.hue_vote {
+            options.hue_vote = hue_vote;
+        }
+        if let Some(achromatic) = self.achromatic {
+            options.achromatic = achromatic;
+        }
+        options.strict |= self.strict;
+        options
+            .mixer_commands
+            .extend(self.mixer_cmd.iter().cloned());
+        #[cfg(feature = "wasm-plugins")]
+        {
+            if self.plugin_dir.is_some() {
+                options.plugin_dir = self.plugin_dir.clone();
+            }
+        }
+    }
+}
+
+/// Flags configuring the rendered page and the other outputs.
+#[derive(Args)]
+pub struct RenderArgs {
+    /// Directory the page is written to.
+    #[arg(long)]
+    pub out_dir: Option<PathBuf>,
+    /// File name of the page, where {seed} and {date} are replaced by the
+    /// seed and day of the run, e.g. run-{seed}-{date}.html.
+    #[arg(long, value_parser = parse_template)]
+    pub html_name: Option<String>,
+    /// File name of the swatch colors, a template like --html-name.
+    #[arg(long, value_parser = parse_template)]
+    pub css_name: Option<String>,
+    /// CSS length of the input swatches, like 4em.
+    #[arg(long)]
+    pub input_swatch_size: Option<String>,
+    /// CSS length of the output swatches.
+    #[arg(long)]
+    pub output_swatch_size: Option<String>,
+    /// Decimal places of printed metrics.
+    #[arg(long)]
+    pub precision: Option<usize>,
+    /// Annotate outputs with their ΔE2000 from `first` input or `rgb-avg`.
+    #[arg(long)]
+    pub annotate_delta: Option<DeltaBaseline>,
+    /// Give every record a name like "muted teal".
+    #[arg(long)]
+    pub palette_names: bool,
+    /// Highlight the output with the smallest ΔE2000 to the inputs.
+    #[arg(long)]
+    pub best_representative: bool,
+    /// Only render records where a mixer failed.
+    #[arg(long)]
+    pub only_failures: bool,
+    /// Also show every output snapped to the nearest color of web-safe,
+    /// ansi16 or a palette file with one color per line.
+    #[arg(long)]
+    pub quantize: Option<Palette>,
+    /// Elevations in dp to render every output on `--surface` at, e.g. 1,4,8.
+    #[arg(long, value_delimiter = ',')]
+    pub elevations: Vec<u8>,
+    /// Surface color the elevations are rendered on.
+    #[arg(long, value_parser = parse_color)]
+    pub surface: Option<RGB>,
+    /// Syntax of colors in the CSS: legacy or modern-rgb.
+    #[arg(long)]
+    pub css_color_format: Option<CssColorFormat>,
+    /// Report how distinguishable the outputs are under protanopia,
+    /// deuteranopia or tritanopia.
+    #[arg(long)]
+    pub cvd: Option<Cvd>,
+    /// ΔE2000 under `--cvd` below which a record is flagged.
+    #[arg(long)]
+    pub cvd_threshold: Option<f32>,
+    /// Pad every record's inputs to the longest record so outputs line up.
+    #[arg(long)]
+    pub align_grid: bool,
+    /// How padded input slots are drawn: hidden, checkerboard or outline.
+    #[arg(long)]
+    pub placeholder_style: Option<PlaceholderStyle>,
+    /// Explain why two mixers differ for every record, e.g. rgb-avg,hsl-geo.
+    #[arg(long, value_parser = parse_pair)]
+    pub explain_diff: Option<(String, String)>,
+    /// Report the inputs' share of the sRGB gamut.
+    #[arg(long)]
+    pub gamut_coverage: bool,
+    /// Stylesheet to write as index.css instead of the built-in one.
+    #[arg(long)]
+    pub stylesheet: Option<PathBuf>,
+    /// Also write the outputs as an SCSS map to this path.
+    #[arg(long)]
+    pub scss: Option<PathBuf>,
+    /// Also write a chromaticity diagram SVG to this path.
+    #[arg(long)]
+    pub chromaticity: Option<PathBuf>,
+    /// Serve the page instead of writing it, on this address.
+    #[cfg(feature = "serve")]
+    #[arg(long, num_args = 0..=1, default_missing_value = "localhost:8000")]
+    pub serve: Option<String>,
+}
+
+impl RenderArgs {
+    fn apply(&self, options: &mut Options) {
+        if let Some(out_dir) = &self.out_dir {
+            options.out_dir = out_dir.clone();
+        }
+        if let Some(html_name) = &self.html_name {
+            options.html_name = html_name.clone();
+        }
+        if let Some(css_name) = &self.css_name {
+            options.css_name = css_name.clone();
+        }
+        if self.input_swatch_size.is_some() {
+            options.input_swatch_size = self.input_swatch_size.clone();
+        }
+        if self.output_swatch_size.is_some() {
+            options.output_swatch_size = self.output_swatch_size.clone();
+        }
+        if let Some(precision) = self.precision {
+            options.precision = precision;
+        }
+        if self.annotate_delta.is_some() {
+            options.annotate_delta = self.annotate_delta;
+        }
+        options.palette_names |= self.palette_names;
+        options.best_representative |= self.best_representative;
+        options.only_failures |= self.only_failures;
+        if self.quantize.is_some() {
+            options.quantize = self.quantize.clone();
+        }
+        if !self.elevations.is_empty() {
+            options.elevations = self.elevations.clone();
+        }
+        if let Some(surface) = self.surface {
+            options.surface = surface;
+        }
+        if let Some(format) = self.css_color_format {
+            options.css_color_format = format;
+        }
+        if self.cvd.is_some() {
+            options.cvd = self.cvd;
+        }
+        if let Some(threshold) = self.cvd_threshold {
+            options.cvd_threshold = threshold;
+        }
+        options.align_grid |= self.align_grid;
+        if let Some(style) = self.placeholder_style {
+            options.placeholder_style = style;
+        }
+        if self.explain_diff.is_some() {
+            options.explain_diff = self.explain_diff.clone();
+        }
+        options.gamut_coverage |= self.gamut_coverage;
+        if self.stylesheet.is_some() {
+            options.stylesheet = self.stylesheet.clone();
+        }
+        if self.scss.is_some() {
+            options.scss = self.scss.clone();
+        }
+        if self.chromaticity.is_some() {
+            options.chromaticity = self.chromaticity.clone();
+        }
+        #[cfg(feature = "serve")]
+        {
+            if self.serve.is_some() {
+                options.serve = self.serve.clone();
+            }
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct GenerateArgs {
+    /// Largest number of inputs per record, records start with 2.
+    #[arg(long, value_parser = parse_max_len)]
+    pub max_len: Option<usize>,
+    /// Records per input count.
+    #[arg(long)]
+    pub rounds: Option<usize>,
+    /// Seed of the random number generator, to reproduce a run.
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Mix the palettes of this JSON, CSV, GIMP palette or ASE swatch file
+    /// instead of random colors, one record each.
+    #[arg(long)]
+    pub input: Option<PathBuf>,
+    /// Mix the palettes of this JSON file like --input, drawing them and
+    /// writing the file first if it does not exist, so successive runs mix
+    /// exactly the same palettes.
+    #[arg(long, conflicts_with = "input")]
+    pub fixtures: Option<PathBuf>,
+    /// Mix the palettes downloaded from this URL like --input, a JSON input
+    /// file or a list of colors, one per line.
+    #[cfg(feature = "from-url")]
+    #[arg(long, conflicts_with_all = ["input", "fixtures", "input_dir"])]
+    pub from_url: Option<String>,
+    /// Mix the palettes of every input file in this directory like --input,
+    /// into one report. Palette names are prefixed with their file name.
+    #[arg(long, conflicts_with_all = ["input", "fixtures"])]
+    pub input_dir: Option<PathBuf>,
+    /// Also write a page for every record to `palettes/<id>.html` in the
+    /// output directory.
+    #[arg(long)]
+    pub palette_pages: bool,
+    /// Sample the inputs from the pixels of this image.
+    #[cfg(feature = "image-input")]
+    #[arg(long)]
+    pub image: Option<PathBuf>,
+    /// Instead of random pixels, mix one record of a WxH grid of `--image`.
+    #[cfg(feature = "image-input")]
+    #[arg(long, requires = "image", value_parser = parse_grid)]
+    pub sample_grid: Option<(u32, u32)>,
+    /// Space the hues of every random palette by the golden angle, for
+    /// well separated inputs. Saturation and lightness follow --preset,
+    /// --sat-range and --light-range.
+    #[arg(long, conflicts_with_all = ["hue_range", "sampling"])]
+    pub golden_hues: bool,
+    /// Move every golden hue randomly by up to this many degrees either way.
+    #[arg(long, requires = "golden_hues", value_parser = parse_jitter)]
+    pub hue_jitter: Option<f32>,
+    /// Draw random colors uniformly from rgb bytes, or from the points of
+    /// oklab or oklch inside of the sRGB gamut for a perceptually even
+    /// spread.
+    #[arg(long, conflicts_with_all = ["preset", "hue_range", "sat_range", "light_range"])]
+    pub sampling: Option<Sampling>,
+    /// Draw random colors from a region of HSL closer to real palettes:
+    /// pastel, vivid, dark or earth. The ranges below override its own.
+    #[arg(long)]
+    pub preset: Option<Preset>,
+    /// Draw random colors only from these HSL hues in degrees, like 180..260
+    /// for blues, wrapping around like 330..30 for reds.
+    #[arg(long, value_parser = parse_hue_range)]
+    pub hue_range: Option<(f32, f32)>,
+    /// Draw random colors only from these HSL saturations in percent, like
+    /// 40..90.
+    #[arg(long, value_parser = parse_percent_range)]
+    pub sat_range: Option<(f32, f32)>,
+    /// Draw random colors only from these HSL lightnesses in percent.
+    #[arg(long, value_parser = parse_percent_range)]
+    pub light_range: Option<(f32, f32)>,
+    /// Also write an animated GIF of the mix shifting as inputs are added.
+    #[cfg(feature = "gif-output")]
+    #[arg(long)]
+    pub gif: Option<PathBuf>,
+    /// Also save the run as JSON to this path, see `render`.
+    #[arg(long)]
+    pub save_json: Option<PathBuf>,
+    #[command(flatten)]
+    pub mixing: MixingArgs,
+    #[command(flatten)]
+    pub render: RenderArgs,
+}
+
+impl GenerateArgs {
+    pub fn options(&self, mut options: Options) -> Options {
+        if let Some(max_len) = self.max_len {
+            options.max_len = max_len;
+        }
+        if let Some(rounds) = self.rounds {
+            options.rounds = rounds;
+        }
+        if self.seed.is_some() {
+            options.seed = self.seed;
+        }
+        options.input = self.input.clone();
+        options.fixtures = self.fixtures.clone();
+        #[cfg(feature = "from-url")]
+        {
+            options.from_url = self.from_url.clone();
+        }
+        options.input_dir = self.input_dir.clone();
+        options.palette_pages = self.palette_pages;
+        #[cfg(feature = "image-input")]
+        {
+            options.image = self.image.clone();
+            options.sample_grid = self.sample_grid;
+        }
+        #[cfg(feature = "gif-output")]
+        {
+            options.gif = self.gif.clone();
+        }
+        options.golden_hues = self.golden_hues.then(|| self.hue_jitter.unwrap_or(0.0));
+        if let Some(sampling) = self.sampling {
+            options.sampling = sampling;
+        }
+        options.preset = self.preset;
+        options.hue_range = self.hue_range;
+        options.sat_range = self.sat_range;
+        options.light_range = self.light_range;
+        options.save_json = self.save_json.clone();
+        self.mixing.apply(&mut options);
+        self.render.apply(&mut options);
+        options
+    }
+}
+
+#[derive(Args)]
+pub struct MixArgs {
+    /// The colors to mix, as hex like #ff8800, with alpha like #ff880080, CSS
+    /// names like tomato or CSS functions like "oklch(0.7 0.1 200)",
+    /// optionally weighted like #ff8800@3. `-` reads colors from stdin, one
+    /// per line.
+    #[arg(required = true)]
+    pub colors: Vec<String>,
+    /// Also render the page for just this record, configured like the page
+    /// of `generate`.
+    #[arg(long)]
+    pub page: bool,
+    #[command(flatten)]
+    pub mixing: MixingArgs,
+    #[command(flatten)]
+    pub render: RenderArgs,
+}
+
+impl MixArgs {
+    pub fn options(&self, mut options: Options) -> Options {
+        self.mixing.apply(&mut options);
+        self.render.apply(&mut options);
+        options
+    }
+
+    /// The colors to mix, with the colors piped to stdin in place of `-`.
+    /// Colors that cannot be parsed fail with their position or, if
+    /// `lenient`, are skipped with a warning.
+    pub fn colors(&self, lenient: bool) -> io::Result<Vec<WeightedColor>> {
+        let mut colors = Vec::new();
+        for (n, arg) in self.colors.iter().enumerate() {
+            if arg == "-" {
+                colors.extend(input::read_colors(io::stdin().lock(), lenient)?);
+                continue;
+            }
+            match parse_weighted_hex(arg) {
+                Ok(color) => colors.push(color),
+                Err(e) if lenient => log::warn!("skipped argument {}: {}", n + 1, e),
+                Err(e) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("argument {}: {}", n + 1, e),
+                    ))
+                }
+            }
+        }
+        if colors.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "there are no colors to mix",
+            ));
+        }
+        Ok(colors)
+    }
+}
+
+#[cfg(feature = "image-input")]
+#[derive(Args)]
+pub struct ExtractArgs {
+    /// The image to extract the colors from.
+    pub image: PathBuf,
+    /// Number of colors to extract, 5 by default.
+    #[arg(long, value_parser = parse_k)]
+    pub colors: Option<usize>,
+    /// Weight every color by its share of the pixels instead of equally.
+    #[arg(long)]
+    pub weighted: bool,
+    /// Also render the page for just this record, configured like the page
+    /// of `generate`.
+    #[arg(long)]
+    pub page: bool,
+    #[command(flatten)]
+    pub mixing: MixingArgs,
+    #[command(flatten)]
+    pub render: RenderArgs,
+}
+
+#[cfg(feature = "image-input")]
+impl ExtractArgs {
+    pub fn options(&self, mut options: Options) -> Options {
+        self.mixing.apply(&mut options);
+        self.render.apply(&mut options);
+        options
+    }
+}
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    /// The color to convert, as hex like #ff8800 or a CSS name like tomato.
+    #[arg(value_parser = parse_color)]
+    pub color: RGB,
+    /// Decimal places of the components.
+    #[arg(long)]
+    pub precision: Option<usize>,
+}
+
+impl ConvertArgs {
+    pub fn options(&self, mut options: Options) -> Options {
+        if let Some(precision) = self.precision {
+            options.precision = precision;
+        }
+        options
+    }
+}
+
+#[derive(Args)]
+pub struct RenderCommandArgs {
+    /// A run saved with `generate --save-json`.
+    pub run: PathBuf,
+    #[command(flatten)]
+    pub mixing: MixingArgs,
+    #[command(flatten)]
+    pub render: RenderArgs,
+}
+
+impl RenderCommandArgs {
+    pub fn options(&self, mut options: Options) -> Options {
+        self.mixing.apply(&mut options);
+        self.render.apply(&mut options);
+        options
+    }
+}
+
+fn parse_max_len(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(max_len) if max_len >= 2 => Ok(max_len),
+        _ => Err(format!(
+            "expected at least 2 since records start with 2 inputs, got `{}`",
+            value
+        )),
+    }
+}
+
+fn parse_trim_fraction(value: &str) -> Result<f32, String> {
+    match value.parse() {
+        Ok(trim) if (0.0..0.5).contains(&trim) => Ok(trim),
+        _ => Err(format!(
+            "expected a fraction of at least 0 and below 0.5, got `{}`",
+            value
+        )),
+    }
+}
+
+fn parse_k(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(k) if k >= 1 => Ok(k),
+        _ => Err(format!("expected at least 1 cluster, got `{}`", value)),
+    }
+}
+
+fn parse_delta_e(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(delta_e) if delta_e.is_finite() && delta_e > 0.0 => Ok(delta_e),
+        _ => Err(format!("expected a positive ΔE like 2, got `{}`", value)),
+    }
+}
+
+fn parse_steps(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(steps) if steps >= 2 => Ok(steps),
+        _ => Err(format!("expected at least 2 steps, got `{}`", value)),
+    }
+}
+
+fn parse_opacity(value: &str) -> Result<f32, String> {
+    match value.parse() {
+        Ok(opacity) if (0.0..=1.0).contains(&opacity) => Ok(opacity),
+        _ => Err(format!("expected an opacity from 0 to 1, got `{}`", value)),
+    }
+}
+
+fn parse_method(slug: &str) -> Result<String, String> {
+    let known = mixer::registry(&Options::default());
+    if known.iter().any(|m| m.slug() == slug) {
+        Ok(slug.to_string())
+    } else {
+        let slugs: Vec<&str> = known.iter().map(|m| m.slug()).collect();
+        Err(format!(
+            "unknown mixer `{}`, expected one of {}",
+            slug,
+            slugs.join(", ")
+        ))
+    }
+}
+
+fn parse_template(template: &str) -> Result<String, String> {
+    run::check_template(template).map(|()| template.to_string())
+}
+
+fn parse_pair(pair: &str) -> Result<(String, String), String> {
+    pair.split_once(',')
+        .map(|(a, b)| (a.to_string(), b.to_string()))
+        .ok_or_else(|| format!("expected two mixers like rgb-avg,hsl-geo, got `{}`", pair))
+}
+
+fn parse_jitter(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(jitter) if (0.0..=180.0).contains(&jitter) => Ok(jitter),
+        _ => Err(format!(
+            "expected a jitter from 0 to 180 degrees, got `{}`",
+            value
+        )),
+    }
+}
+
+/// Parses a range like `40..90`.
+fn parse_range(range: &str) -> Option<(f32, f32)> {
+    let (min, max) = range.split_once("..")?;
+    let (min, max) = (
+        min.trim().parse::<f32>().ok()?,
+        max.trim().parse::<f32>().ok()?,
+    );
+    Some((min, max)).filter(|_| min.is_finite() && max.is_finite())
+}
+
+fn parse_hue_range(range: &str) -> Result<(f32, f32), String> {
+    match parse_range(range) {
+        Some((min, max)) if (0.0..=360.0).contains(&min) && (0.0..=360.0).contains(&max) => {
+            Ok((min, max))
+        }
+        _ => Err(format!(
+            "expected hues from 0 to 360 like 180..260, got `{}`",
+            range
+        )),
+    }
+}
+
+fn parse_percent_range(range: &str) -> Result<(f32, f32), String> {
+    match parse_range(range) {
+        Some((min, max)) if 0.0 <= min && min <= max && max <= 100.0 => Ok((min, max)),
+        _ => Err(format!(
+            "expected percentages from 0 to 100 like 40..90, got `{}`",
+            range
+        )),
+    }
+}
+
+#[cfg(feature = "image-input")]
+fn parse_grid(grid: &str) -> Result<(u32, u32), String> {
+    match grid
+        .split_once('x')
+        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+    {
+        Some((columns, rows)) if columns > 0 && rows > 0 => Ok((columns, rows)),
+        Some(_) => Err(format!(
+            "expected a grid of at least 1x1 cells, got `{}`",
+            grid
+        )),
+        None => Err(format!("expected WxH, e.g. 4x4, got `{}`", grid)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_len_below_two_is_rejected() {
+        assert_eq!(parse_max_len("2"), Ok(2));
+        assert_eq!(
+            parse_max_len("1").unwrap_err(),
+            "expected at least 2 since records start with 2 inputs, got `1`"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "image-input")]
+    fn grid_without_cells_is_rejected() {
+        assert_eq!(parse_grid("4x3"), Ok((4, 3)));
+        for grid in ["0x0", "0x3", "4x0"] {
+            assert_eq!(
+                parse_grid(grid).unwrap_err(),
+                format!("expected a grid of at least 1x1 cells, got `{}`", grid)
+            );
+        }
+    }
+}
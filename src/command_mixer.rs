@@ -0,0 +1,91 @@
+//! Mixers run as external programs, to prototype strategies in any language
+//! next to the built-in ones.
+//!
+//! The program gets the inputs as one line of JSON on stdin, like
+//! `{"colors":["#ff0000","#0000ff"],"weights":[1.0,1.0]}`, and prints the
+//! mix as a hex color on stdout. A JSON string like `"#7f007f"` works too.
+//! A program exiting with an error fails the mixer with its stderr.
+
+use crate::mixer::Mixer;
+use crate::options::{check_slug, parse_hex};
+use crate::output::to_hex;
+use crate::ComputeError;
+use css_colors::RGB;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+/// A mixer running `command` for every mix, parsed from `name=command`.
+/// The command is split at whitespace into the program and its arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandMixer {
+    pub name: String,
+    pub command: String,
+}
+
+impl CommandMixer {
+    /// Runs the command on the inputs and parses its output.
+    fn call(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, String> {
+        let mut words = self.command.split_whitespace();
+        let program = words.next().ok_or("the command is empty")?;
+        let request = serde_json::json!({
+            "colors": input.iter().map(|c| to_hex(*c)).collect::<Vec<_>>(),
+            "weights": weights,
+        });
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("cannot run `{}`: {}", self.command, e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "{}", request).map_err(|e| e.to_string())?;
+        }
+        let output = child.wait_with_output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!(
+                "`{}` {}: {}",
+                self.command,
+                output.status,
+                stderr.trim()
+            ));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        parse_hex(stdout.trim().trim_matches('"'))
+            .map_err(|e| format!("`{}` printed no color: {}", self.command, e))
+    }
+}
+
+impl FromStr for CommandMixer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, command) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected name=command, got `{}`", s))?;
+        check_slug(name)?;
+        if command.trim().is_empty() {
+            return Err(format!("the command of `{}` is empty", name));
+        }
+        Ok(CommandMixer {
+            name: name.to_string(),
+            command: command.to_string(),
+        })
+    }
+}
+
+impl Mixer for CommandMixer {
+    fn slug(&self) -> &str {
+        &self.name
+    }
+
+    fn describe(&self) -> String {
+        format!("mixed by running {}", self.command)
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        self.call(input, weights).map_err(ComputeError::Plugin)
+    }
+}
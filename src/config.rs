@@ -0,0 +1,123 @@
+//! The `color-mix.toml` configuration file.
+//!
+//! ```toml
+//! max-len = 6
+//! rounds = 4
+//! out-dir = "site"
+//! html-name = "run-{seed}-{date}.html"
+//! css-name = "colors-{seed}.css"
+//! mixers = ["rgb-avg", "hsl-geo"]
+//! input-swatch-size = "3em"
+//! output-swatch-size = "6em"
+//! achromatic = "skip"
+//! ```
+
+use crate::mixer;
+use crate::run;
+use crate::Options;
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The file loaded from the working directory if no other is given.
+pub const DEFAULT_PATH: &str = "color-mix.toml";
+
+/// Generation parameters read from a configuration file. Every key is
+/// optional and leaves the default in place when missing.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub max_len: Option<usize>,
+    pub rounds: Option<usize>,
+    pub out_dir: Option<PathBuf>,
+    /// File name template of the page, see `RunMetadata::file_name`.
+    pub html_name: Option<String>,
+    /// File name template of the swatch colors.
+    pub css_name: Option<String>,
+    /// Slugs of the mixers to run, all of them if missing.
+    pub mixers: Option<Vec<String>>,
+    /// CSS length of the input swatches, like `4em`.
+    pub input_swatch_size: Option<String>,
+    /// CSS length of the output swatches.
+    pub output_swatch_size: Option<String>,
+    /// What hsl-geo does with gray inputs: vote, skip, rgb-avg or error.
+    pub achromatic: Option<String>,
+}
+
+impl Config {
+    pub fn from_toml(toml: &str) -> Result<Config, String> {
+        toml::from_str(toml).map_err(|e| e.to_string())
+    }
+
+    /// Loads the configuration at `path`, or at `DEFAULT_PATH` if that file
+    /// exists. Without either, the configuration is empty.
+    pub fn load(path: Option<&Path>) -> io::Result<Config> {
+        let (path, required) = match path {
+            Some(path) => (path, true),
+            None => (Path::new(DEFAULT_PATH), false),
+        };
+        let toml = match fs::read_to_string(path) {
+            Ok(toml) => toml,
+            Err(e) if !required && e.kind() == io::ErrorKind::NotFound => {
+                return Ok(Config::default())
+            }
+            Err(e) => return Err(e),
+        };
+        Config::from_toml(&toml).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })
+    }
+
+    /// Overrides the fields of `options` that are set in the configuration.
+    pub fn apply(&self, options: &mut Options) -> Result<(), String> {
+        if let Some(max_len) = self.max_len {
+            if max_len < 2 {
+                return Err(format!(
+                    "max-len must be at least 2 since records start with 2 inputs, got {}",
+                    max_len
+                ));
+            }
+            options.max_len = max_len;
+        }
+        if let Some(rounds) = self.rounds {
+            options.rounds = rounds;
+        }
+        if let Some(out_dir) = &self.out_dir {
+            options.out_dir = out_dir.clone();
+        }
+        if let Some(html_name) = &self.html_name {
+            run::check_template(html_name).map_err(|e| format!("html-name: {}", e))?;
+            options.html_name = html_name.clone();
+        }
+        if let Some(css_name) = &self.css_name {
+            run::check_template(css_name).map_err(|e| format!("css-name: {}", e))?;
+            options.css_name = css_name.clone();
+        }
+        if let Some(mixers) = &self.mixers {
+            let known = mixer::registry(&Options::default());
+            if let Some(unknown) = mixers
+                .iter()
+                .find(|s| !known.iter().any(|m| m.slug() == *s))
+            {
+                return Err(format!("unknown mixer `{}` in mixers", unknown));
+            }
+            options.mixers = Some(mixers.clone());
+        }
+        if self.input_swatch_size.is_some() {
+            options.input_swatch_size = self.input_swatch_size.clone();
+        }
+        if self.output_swatch_size.is_some() {
+            options.output_swatch_size = self.output_swatch_size.clone();
+        }
+        if let Some(achromatic) = &self.achromatic {
+            options.achromatic = achromatic
+                .parse()
+                .map_err(|e| format!("achromatic: {}", e))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,463 @@
+//! Conversions from sRGB into the device independent color spaces used for
+//! measuring and mixing colors, and back.
+
+use css_colors::{Angle, Ratio, HSL, RGB};
+
+/// D65 reference white in CIE XYZ, scaled so that `Y = 1.0`.
+const WHITE_D65: [f32; 3] = [0.950_47, 1.0, 1.088_83];
+
+/// D50 reference white in CIE XYZ, scaled so that `Y = 1.0`.
+const WHITE_D50: [f32; 3] = [0.964_22, 1.0, 0.825_21];
+
+/// Bradford chromatic adaptation from D50 to D65.
+const D50_TO_D65: [[f32; 3]; 3] = [
+    [0.955_473_4, -0.023_098_45, 0.063_259_24],
+    [-0.028_369_71, 1.009_995_4, 0.021_041_44],
+    [0.012_314_01, -0.020_507_65, 1.330_366],
+];
+
+/// A color in CIELAB (D65), with `l` in `0..=100`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+/// A color in CIE LCh(ab), the polar form of `Lab`, with the hue `h` in
+/// degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+/// A color in Björn Ottosson's OKLab, with `l` in `0..=1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+/// A color in OKLCH, the polar form of `Oklab`, with the hue `h` in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+/// A color in HSV, with the hue `h` in degrees and `s` and `v` in
+/// `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+}
+
+/// A color in naive CMYK, every ink in `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cmyk {
+    pub c: f32,
+    pub m: f32,
+    pub y: f32,
+    pub k: f32,
+}
+
+/// Removes the sRGB transfer curve from a channel in `0.0..=1.0`.
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Applies the sRGB transfer curve to a linear channel in `0.0..=1.0`.
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts a color to linear-light RGB channels in `0.0..=1.0`.
+pub fn rgb_to_linear(color: RGB) -> [f32; 3] {
+    [
+        srgb_to_linear(color.r.as_f32()),
+        srgb_to_linear(color.g.as_f32()),
+        srgb_to_linear(color.b.as_f32()),
+    ]
+}
+
+/// Converts linear-light RGB channels back to a color, clamping anything
+/// outside of the sRGB gamut.
+pub fn linear_to_rgb([r, g, b]: [f32; 3]) -> RGB {
+    let channel =
+        |value: f32| Ratio::from_f32(linear_to_srgb(value.clamp(0.0, 1.0)).clamp(0.0, 1.0));
+    RGB {
+        r: channel(r),
+        g: channel(g),
+        b: channel(b),
+    }
+}
+
+/// Converts a color to CIE XYZ (D65) with `Y = 1.0` for white.
+pub fn rgb_to_xyz(color: RGB) -> [f32; 3] {
+    let [r, g, b] = rgb_to_linear(color);
+
+    [
+        0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b,
+        0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b,
+        0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b,
+    ]
+}
+
+/// Converts CIE XYZ (D65) back to sRGB, clamping out of gamut results.
+pub fn xyz_to_rgb([x, y, z]: [f32; 3]) -> RGB {
+    linear_to_rgb([
+        3.240_454_2 * x - 1.537_138_5 * y - 0.498_531_4 * z,
+        -0.969_266 * x + 1.876_010_8 * y + 0.041_556 * z,
+        0.055_643_4 * x - 0.204_025_9 * y + 1.057_225_2 * z,
+    ])
+}
+
+/// Converts CIE XYZ to LMS cone responses with the Hunt-Pointer-Estévez
+/// matrix, normalized so that equal-energy white has equal responses.
+pub fn xyz_to_lms([x, y, z]: [f32; 3]) -> [f32; 3] {
+    [
+        0.400_2 * x + 0.707_6 * y - 0.080_8 * z,
+        -0.226_3 * x + 1.165_3 * y + 0.045_7 * z,
+        0.918_2 * z,
+    ]
+}
+
+/// Converts LMS cone responses back to CIE XYZ.
+pub fn lms_to_xyz([l, m, s]: [f32; 3]) -> [f32; 3] {
+    [
+        1.860_066_6 * l - 1.129_480_1 * m + 0.219_898_3 * s,
+        0.361_222_9 * l + 0.638_804_3 * m - 0.000_007_1 * s,
+        1.089_087_3 * s,
+    ]
+}
+
+/// Converts a color to LMS cone responses, see [`xyz_to_lms`].
+pub fn rgb_to_lms(color: RGB) -> [f32; 3] {
+    xyz_to_lms(rgb_to_xyz(color))
+}
+
+/// Converts LMS cone responses back to sRGB, clamping out of gamut results.
+pub fn lms_to_rgb(lms: [f32; 3]) -> RGB {
+    xyz_to_rgb(lms_to_xyz(lms))
+}
+
+/// Luma coefficients of red and blue in BT.709.
+const BT709_KR: f32 = 0.2126;
+const BT709_KB: f32 = 0.0722;
+
+/// Converts a color to BT.709 Y'CbCr on the 8-bit studio range scale, `Y'`
+/// in `16..=235` and `Cb` and `Cr` in `16..=240`. The codes are not rounded,
+/// so that every color converts back to itself.
+pub fn rgb_to_ycbcr(color: RGB) -> [f32; 3] {
+    let (r, g, b) = (color.r.as_f32(), color.g.as_f32(), color.b.as_f32());
+    let y = BT709_KR * r + (1.0 - BT709_KR - BT709_KB) * g + BT709_KB * b;
+    let cb = (b - y) / (2.0 * (1.0 - BT709_KB));
+    let cr = (r - y) / (2.0 * (1.0 - BT709_KR));
+    [16.0 + 219.0 * y, 128.0 + 224.0 * cb, 128.0 + 224.0 * cr]
+}
+
+/// Converts BT.709 Y'CbCr studio range codes back to sRGB, rounding every
+/// channel and clamping out of gamut results.
+pub fn ycbcr_to_rgb([y, cb, cr]: [f32; 3]) -> RGB {
+    let (y, cb, cr) = (
+        (y - 16.0) / 219.0,
+        (cb - 128.0) / 224.0,
+        (cr - 128.0) / 224.0,
+    );
+    let r = y + 2.0 * (1.0 - BT709_KR) * cr;
+    let b = y + 2.0 * (1.0 - BT709_KB) * cb;
+    let g = (y - BT709_KR * r - BT709_KB * b) / (1.0 - BT709_KR - BT709_KB);
+    let channel = |value: f32| Ratio::from_f32(value.clamp(0.0, 1.0));
+    RGB {
+        r: channel(r),
+        g: channel(g),
+        b: channel(b),
+    }
+}
+
+/// CIE 1931 xy chromaticity of the D65 white point.
+pub const WHITE_D65_XY: [f32; 2] = [0.3127, 0.3290];
+
+/// Converts a color to CIE 1931 xy chromaticity coordinates. Black has no
+/// chromaticity and is mapped onto the white point.
+pub fn rgb_to_xy(color: RGB) -> [f32; 2] {
+    let [x, y, z] = rgb_to_xyz(color);
+    let sum = x + y + z;
+    if sum <= f32::EPSILON {
+        return WHITE_D65_XY;
+    }
+    [x / sum, y / sum]
+}
+
+/// Converts a color to CIELAB relative to the D65 white point.
+pub fn rgb_to_lab(color: RGB) -> Lab {
+    fn f(t: f32) -> f32 {
+        let delta: f32 = 6.0 / 29.0;
+        if t > delta.powi(3) {
+            t.cbrt()
+        } else {
+            t / (3.0 * delta * delta) + 4.0 / 29.0
+        }
+    }
+
+    let [x, y, z] = rgb_to_xyz(color);
+    let fx = f(x / WHITE_D65[0]);
+    let fy = f(y / WHITE_D65[1]);
+    let fz = f(z / WHITE_D65[2]);
+
+    Lab {
+        l: 116.0 * fy - 16.0,
+        a: 500.0 * (fx - fy),
+        b: 200.0 * (fy - fz),
+    }
+}
+
+/// Converts a CIELAB color relative to `white` to CIE XYZ.
+fn lab_to_xyz(color: Lab, white: [f32; 3]) -> [f32; 3] {
+    fn f_inv(t: f32) -> f32 {
+        let delta: f32 = 6.0 / 29.0;
+        if t > delta {
+            t.powi(3)
+        } else {
+            3.0 * delta * delta * (t - 4.0 / 29.0)
+        }
+    }
+
+    let fy = (color.l + 16.0) / 116.0;
+    let fx = fy + color.a / 500.0;
+    let fz = fy - color.b / 200.0;
+
+    [
+        white[0] * f_inv(fx),
+        white[1] * f_inv(fy),
+        white[2] * f_inv(fz),
+    ]
+}
+
+/// Converts a CIELAB color back to sRGB, clamping out of gamut results.
+pub fn lab_to_rgb(color: Lab) -> RGB {
+    xyz_to_rgb(lab_to_xyz(color, WHITE_D65))
+}
+
+/// Converts a CIELAB color relative to the D50 white point, as in CSS
+/// `lab()` and swatch files, to sRGB with the Bradford adaptation of CSS
+/// Color 4, clamping out of gamut results.
+pub fn lab_d50_to_rgb(color: Lab) -> RGB {
+    let d50 = lab_to_xyz(color, WHITE_D50);
+    let row = |m: [f32; 3]| m[0] * d50[0] + m[1] * d50[1] + m[2] * d50[2];
+    xyz_to_rgb([row(D50_TO_D65[0]), row(D50_TO_D65[1]), row(D50_TO_D65[2])])
+}
+
+/// Converts CIELAB to its polar form, with the hue in `0.0..360.0`.
+pub fn lab_to_lch(color: Lab) -> Lch {
+    Lch {
+        l: color.l,
+        c: color.a.hypot(color.b),
+        h: color.b.atan2(color.a).to_degrees().rem_euclid(360.0),
+    }
+}
+
+/// Converts CIE LCh(ab) back to CIELAB.
+pub fn lch_to_lab(color: Lch) -> Lab {
+    let (sin, cos) = color.h.to_radians().sin_cos();
+    Lab {
+        l: color.l,
+        a: color.c * cos,
+        b: color.c * sin,
+    }
+}
+
+/// Converts OKLab to its polar form OKLCH, with the hue in `0.0..360.0`.
+pub fn oklab_to_oklch(color: Oklab) -> Oklch {
+    Oklch {
+        l: color.l,
+        c: color.a.hypot(color.b),
+        h: color.b.atan2(color.a).to_degrees().rem_euclid(360.0),
+    }
+}
+
+/// Converts OKLCH back to OKLab.
+pub fn oklch_to_oklab(color: Oklch) -> Oklab {
+    let (sin, cos) = color.h.to_radians().sin_cos();
+    Oklab {
+        l: color.l,
+        a: color.c * cos,
+        b: color.c * sin,
+    }
+}
+
+/// Converts a color to OKLab.
+pub fn rgb_to_oklab(color: RGB) -> Oklab {
+    let [r, g, b] = rgb_to_linear(color);
+
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+
+    Oklab {
+        l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    }
+}
+
+/// Converts an OKLab color to linear sRGB channels, which are outside of
+/// `0.0..=1.0` for colors outside of the sRGB gamut.
+pub fn oklab_to_linear(color: Oklab) -> [f32; 3] {
+    let l = (color.l + 0.396_337_78 * color.a + 0.215_803_76 * color.b).powi(3);
+    let m = (color.l - 0.105_561_346 * color.a - 0.063_854_17 * color.b).powi(3);
+    let s = (color.l - 0.089_484_18 * color.a - 1.291_485_5 * color.b).powi(3);
+
+    [
+        4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+        -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+        -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+    ]
+}
+
+/// Whether OKLab `color` is inside of the sRGB gamut, up to rounding.
+pub fn oklab_in_gamut(color: Oklab) -> bool {
+    oklab_to_linear(color)
+        .iter()
+        .all(|c| (-1e-4..=1.0 + 1e-4).contains(c))
+}
+
+/// Converts an OKLab color back to sRGB, clamping out of gamut results.
+pub fn oklab_to_rgb(color: Oklab) -> RGB {
+    linear_to_rgb(oklab_to_linear(color))
+}
+
+/// Converts to HSL like `Color::to_hsl`, which panics when rounding pushes
+/// the saturation of colors like `rgb(1, 1, 255)` above 100%. The result is
+/// clamped instead.
+pub fn rgb_to_hsl(color: RGB) -> HSL {
+    let (r, g, b) = (color.r.as_f32(), color.g.as_f32(), color.b.as_f32());
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    if max == min {
+        return HSL {
+            h: Angle::new(0),
+            s: Ratio::from_u8(0),
+            l: color.r,
+        };
+    }
+
+    let saturation = if lightness < 0.5 {
+        (max - min) / (max + min)
+    } else {
+        (max - min) / (2.0 - (max + min))
+    };
+    let hue = if max == r {
+        60.0 * (g - b) / (max - min)
+    } else if max == g {
+        120.0 + 60.0 * (b - r) / (max - min)
+    } else {
+        240.0 + 60.0 * (r - g) / (max - min)
+    };
+
+    HSL {
+        h: Angle::new((hue.round() as i32).rem_euclid(360) as u16),
+        s: Ratio::from_f32(saturation.clamp(0.0, 1.0)),
+        l: Ratio::from_f32(lightness.clamp(0.0, 1.0)),
+    }
+}
+
+/// Converts HSL with the hue in degrees and saturation and lightness in
+/// `0.0..=1.0` to sRGB, without the rounding of `HSL::to_rgb`.
+pub fn hsl_to_rgb(h: f32, s: f32, l: f32) -> RGB {
+    let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+    let v = l + s * l.min(1.0 - l);
+    let s = if v <= 0.0 { 0.0 } else { 2.0 * (1.0 - l / v) };
+    hsv_to_rgb(Hsv { h, s, v })
+}
+
+/// Converts a color to HSV. Grays get a hue of 0°.
+pub fn rgb_to_hsv(color: RGB) -> Hsv {
+    let (r, g, b) = (color.r.as_f32(), color.g.as_f32(), color.b.as_f32());
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    let h = if chroma == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * (g - b) / chroma
+    } else if max == g {
+        120.0 + 60.0 * (b - r) / chroma
+    } else {
+        240.0 + 60.0 * (r - g) / chroma
+    };
+    Hsv {
+        h: h.rem_euclid(360.0),
+        s: if max == 0.0 { 0.0 } else { chroma / max },
+        v: max,
+    }
+}
+
+/// Converts HSV back to sRGB, clamping saturation and value to `0.0..=1.0`.
+pub fn hsv_to_rgb(color: Hsv) -> RGB {
+    let (s, v) = (color.s.clamp(0.0, 1.0), color.v.clamp(0.0, 1.0));
+    let chroma = v * s;
+    let sector = color.h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = v - chroma;
+    let channel = |value: f32| Ratio::from_f32((value + m).clamp(0.0, 1.0));
+    RGB {
+        r: channel(r),
+        g: channel(g),
+        b: channel(b),
+    }
+}
+
+/// Converts a color to naive CMYK, with maximal black and no ink profile.
+pub fn rgb_to_cmyk(color: RGB) -> Cmyk {
+    let (r, g, b) = (color.r.as_f32(), color.g.as_f32(), color.b.as_f32());
+    let k = 1.0 - r.max(g).max(b);
+    if k >= 1.0 {
+        return Cmyk {
+            c: 0.0,
+            m: 0.0,
+            y: 0.0,
+            k: 1.0,
+        };
+    }
+    Cmyk {
+        c: (1.0 - r - k) / (1.0 - k),
+        m: (1.0 - g - k) / (1.0 - k),
+        y: (1.0 - b - k) / (1.0 - k),
+        k,
+    }
+}
+
+/// Converts naive CMYK back to sRGB.
+pub fn cmyk_to_rgb(color: Cmyk) -> RGB {
+    let white = 1.0 - color.k.clamp(0.0, 1.0);
+    let channel = |ink: f32| Ratio::from_f32(((1.0 - ink.clamp(0.0, 1.0)) * white).clamp(0.0, 1.0));
+    RGB {
+        r: channel(color.c),
+        g: channel(color.m),
+        b: channel(color.y),
+    }
+}
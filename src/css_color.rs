@@ -0,0 +1,199 @@
+//! Parsing the functional color notations of CSS Color 4, like
+//! `rgb(12 34 56)`, `hsl(200 50% 40%)`, `lab(52% 40 -30)` and
+//! `oklch(0.7 0.1 200 / 50%)`.
+//!
+//! Both the modern space separated syntax with an optional `/ alpha` and the
+//! legacy comma separated syntax of `rgb()`, `rgba()`, `hsl()` and `hsla()`
+//! are accepted, as is `none` for a missing component. Colors outside of the
+//! sRGB gamut are clipped. As in CSS, `lab()` and `lch()` are relative to the
+//! D50 white point, see [`lab_d50_to_rgb`].
+
+use crate::convert::{hsl_to_rgb, hsv_to_rgb, lab_d50_to_rgb, oklab_to_rgb, oklch_to_oklab};
+use crate::convert::{Hsv, Lab, Oklab, Oklch};
+use css_colors::{Ratio, RGB};
+
+/// Whether `color` looks like a color function, `name(...)`.
+pub fn is_function(color: &str) -> bool {
+    color.trim_end().ends_with(')') && color.contains('(')
+}
+
+/// One component of a color function.
+#[derive(Debug, Clone, Copy)]
+enum Component {
+    Number(f32),
+    Percentage(f32),
+    /// A number in degrees, from an angle with a unit.
+    Angle(f32),
+    None,
+}
+
+impl Component {
+    fn parse(token: &str) -> Option<Component> {
+        let token = token.to_ascii_lowercase();
+        if token == "none" {
+            return Some(Component::None);
+        }
+        let number = |value: &str| value.parse::<f32>().ok().filter(|v| v.is_finite());
+        if let Some(value) = token.strip_suffix('%') {
+            return number(value).map(Component::Percentage);
+        }
+        let units: [(&str, f32); 4] = [
+            ("deg", 1.0),
+            ("grad", 0.9),
+            ("rad", 180.0 / std::f32::consts::PI),
+            ("turn", 360.0),
+        ];
+        for (unit, degrees) in units.iter() {
+            if let Some(value) = token.strip_suffix(unit) {
+                return number(value).map(|v| Component::Angle(v * degrees));
+            }
+        }
+        number(&token).map(Component::Number)
+    }
+
+    /// The value of the component, where 100% is `full`. `None` if it is an
+    /// angle where no angle is allowed.
+    fn value(self, full: f32) -> Option<f32> {
+        match self {
+            Component::Number(value) => Some(value),
+            Component::Percentage(value) => Some(value / 100.0 * full),
+            Component::Angle(_) => None,
+            Component::None => Some(0.0),
+        }
+    }
+
+    /// The value of a hue component in degrees.
+    fn hue(self) -> Option<f32> {
+        match self {
+            Component::Number(value) | Component::Angle(value) => Some(value),
+            Component::Percentage(_) => None,
+            Component::None => Some(0.0),
+        }
+    }
+}
+
+/// Splits the arguments of a color function into its three components and
+/// the optional alpha.
+fn split(arguments: &str, legacy: bool) -> Option<([Component; 3], Option<Component>)> {
+    let tokens: Vec<&str> = if arguments.contains(',') {
+        if !legacy {
+            return None;
+        }
+        arguments.split(',').map(str::trim).collect()
+    } else {
+        let (components, alpha) = match arguments.split_once('/') {
+            Some((components, alpha)) => (components, Some(alpha.trim())),
+            None => (arguments, None),
+        };
+        let mut tokens: Vec<&str> = components.split_whitespace().collect();
+        if let Some(alpha) = alpha {
+            if tokens.len() != 3 || alpha.split_whitespace().count() != 1 {
+                return None;
+            }
+            tokens.push(alpha);
+        }
+        tokens
+    };
+    let components: Vec<Component> = tokens
+        .iter()
+        .map(|token| Component::parse(token))
+        .collect::<Option<_>>()?;
+    match components[..] {
+        [a, b, c] => Some(([a, b, c], None)),
+        [a, b, c, alpha] => Some(([a, b, c], Some(alpha))),
+        _ => None,
+    }
+}
+
+/// Converts HWB with whiteness and blackness in `0.0..=1.0` to sRGB.
+fn hwb_to_rgb(h: f32, w: f32, b: f32) -> RGB {
+    let (w, b) = (w.clamp(0.0, 1.0), b.clamp(0.0, 1.0));
+    if w + b >= 1.0 {
+        let gray = Ratio::from_f32(w / (w + b));
+        return RGB {
+            r: gray,
+            g: gray,
+            b: gray,
+        };
+    }
+    let v = 1.0 - b;
+    hsv_to_rgb(Hsv {
+        h,
+        s: 1.0 - w / v,
+        v,
+    })
+}
+
+/// Parses a CSS color function into a color and its alpha in `0.0..=1.0`.
+pub fn parse(color: &str) -> Result<(RGB, f32), String> {
+    let error = || {
+        format!(
+            "`{}` is not a CSS color like rgb(12 34 56), hsl(200 50% 40%), lab(52% 40 -30) \
+             or oklch(0.7 0.1 200)",
+            color
+        )
+    };
+    let (name, arguments) = color
+        .trim()
+        .strip_suffix(')')
+        .and_then(|c| c.split_once('('))
+        .ok_or_else(error)?;
+    let name = name.trim().to_ascii_lowercase();
+    let legacy = matches!(name.as_str(), "rgb" | "rgba" | "hsl" | "hsla");
+    let ([x, y, z], alpha) = split(arguments, legacy).ok_or_else(error)?;
+    let alpha = match alpha {
+        Some(alpha) => alpha.value(1.0).ok_or_else(error)?.clamp(0.0, 1.0),
+        None => 1.0,
+    };
+
+    let rgb = match name.as_str() {
+        "rgb" | "rgba" => {
+            let channel = |c: Component| {
+                c.value(255.0)
+                    .map(|v| Ratio::from_f32((v / 255.0).clamp(0.0, 1.0)))
+            };
+            RGB {
+                r: channel(x).ok_or_else(error)?,
+                g: channel(y).ok_or_else(error)?,
+                b: channel(z).ok_or_else(error)?,
+            }
+        }
+        "hsl" | "hsla" => hsl_to_rgb(
+            x.hue().ok_or_else(error)?,
+            y.value(100.0).ok_or_else(error)? / 100.0,
+            z.value(100.0).ok_or_else(error)? / 100.0,
+        ),
+        "hwb" => hwb_to_rgb(
+            x.hue().ok_or_else(error)?,
+            y.value(100.0).ok_or_else(error)? / 100.0,
+            z.value(100.0).ok_or_else(error)? / 100.0,
+        ),
+        "lab" => lab_d50_to_rgb(Lab {
+            l: x.value(100.0).ok_or_else(error)?.clamp(0.0, 100.0),
+            a: y.value(125.0).ok_or_else(error)?,
+            b: z.value(125.0).ok_or_else(error)?,
+        }),
+        "lch" => {
+            let l = x.value(100.0).ok_or_else(error)?.clamp(0.0, 100.0);
+            let c = y.value(150.0).ok_or_else(error)?.max(0.0);
+            let (sin, cos) = z.hue().ok_or_else(error)?.to_radians().sin_cos();
+            lab_d50_to_rgb(Lab {
+                l,
+                a: c * cos,
+                b: c * sin,
+            })
+        }
+        "oklab" => oklab_to_rgb(Oklab {
+            l: x.value(1.0).ok_or_else(error)?.clamp(0.0, 1.0),
+            a: y.value(0.4).ok_or_else(error)?,
+            b: z.value(0.4).ok_or_else(error)?,
+        }),
+        "oklch" => oklab_to_rgb(oklch_to_oklab(Oklch {
+            l: x.value(1.0).ok_or_else(error)?.clamp(0.0, 1.0),
+            c: y.value(0.4).ok_or_else(error)?.max(0.0),
+            h: z.hue().ok_or_else(error)?,
+        })),
+        _ => return Err(error()),
+    };
+    Ok((rgb, alpha))
+}
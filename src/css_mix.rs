@@ -0,0 +1,279 @@
+//! The CSS `color-mix()` function of CSS Color 5, so that results can be
+//! checked against a browser.
+//!
+//! `color-mix()` mixes two colors, more colors are mixed by nesting it. Both
+//! colors are converted to the interpolation space, premultiplied by their
+//! alpha and interpolated linearly, hues along a [`HuePath`]. A hue that is
+//! powerless, like that of a gray, is missing and takes the other color's.
+
+use crate::blend::{interpolate_hue, HuePath};
+use crate::convert::{hsv_to_rgb, lab_to_lch, lab_to_rgb, lch_to_lab, linear_to_rgb};
+use crate::convert::{oklab_to_oklch, oklab_to_rgb, oklch_to_oklab, rgb_to_hsv, rgb_to_lab};
+use crate::convert::{rgb_to_linear, rgb_to_oklab, rgb_to_xyz, xyz_to_rgb};
+use crate::convert::{Hsv, Lab, Lch, Oklab, Oklch};
+use crate::output::to_hex;
+use css_colors::{Ratio, RGB};
+use std::str::FromStr;
+
+/// A `<color-space>` of `color-mix(in <color-space>, ...)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CssMixSpace {
+    Srgb,
+    SrgbLinear,
+    Lab,
+    /// The default of CSS.
+    #[default]
+    Oklab,
+    Xyz,
+    Hsl,
+    Lch,
+    Oklch,
+}
+
+impl CssMixSpace {
+    /// The CSS keyword of the space.
+    pub fn name(self) -> &'static str {
+        match self {
+            CssMixSpace::Srgb => "srgb",
+            CssMixSpace::SrgbLinear => "srgb-linear",
+            CssMixSpace::Lab => "lab",
+            CssMixSpace::Oklab => "oklab",
+            CssMixSpace::Xyz => "xyz",
+            CssMixSpace::Hsl => "hsl",
+            CssMixSpace::Lch => "lch",
+            CssMixSpace::Oklch => "oklch",
+        }
+    }
+
+    /// Whether the third component is a hue.
+    pub fn is_polar(self) -> bool {
+        matches!(
+            self,
+            CssMixSpace::Hsl | CssMixSpace::Lch | CssMixSpace::Oklch
+        )
+    }
+
+    /// The components of `color` in the space, with the hue last for polar
+    /// spaces, and whether the hue is not powerless.
+    fn components(self, color: RGB) -> ([f32; 3], bool) {
+        let channels = [color.r, color.g, color.b].map(|c| c.as_f32());
+        match self {
+            CssMixSpace::Srgb => (channels, true),
+            CssMixSpace::SrgbLinear => (rgb_to_linear(color), true),
+            CssMixSpace::Lab => {
+                let Lab { l, a, b } = rgb_to_lab(color);
+                ([l, a, b], true)
+            }
+            CssMixSpace::Oklab => {
+                let Oklab { l, a, b } = rgb_to_oklab(color);
+                ([l, a, b], true)
+            }
+            CssMixSpace::Xyz => (rgb_to_xyz(color), true),
+            CssMixSpace::Hsl => {
+                let Hsv { h, s, v } = rgb_to_hsv(color);
+                let l = v * (1.0 - s / 2.0);
+                let s = if l <= 0.0 || l >= 1.0 {
+                    0.0
+                } else {
+                    (v - l) / l.min(1.0 - l)
+                };
+                ([s, l, h], s > 0.0)
+            }
+            CssMixSpace::Lch => {
+                let Lch { l, c, h } = lab_to_lch(rgb_to_lab(color));
+                ([l, c, h], c >= 1e-2)
+            }
+            CssMixSpace::Oklch => {
+                let Oklch { l, c, h } = oklab_to_oklch(rgb_to_oklab(color));
+                ([l, c, h], c >= 1e-4)
+            }
+        }
+    }
+
+    /// Converts components of the space back to sRGB, clipping anything out
+    /// of gamut.
+    fn to_rgb(self, [x, y, z]: [f32; 3]) -> RGB {
+        let channel = |value: f32| Ratio::from_f32(value.clamp(0.0, 1.0));
+        match self {
+            CssMixSpace::Srgb => RGB {
+                r: channel(x),
+                g: channel(y),
+                b: channel(z),
+            },
+            CssMixSpace::SrgbLinear => linear_to_rgb([x, y, z]),
+            CssMixSpace::Lab => lab_to_rgb(Lab { l: x, a: y, b: z }),
+            CssMixSpace::Oklab => oklab_to_rgb(Oklab { l: x, a: y, b: z }),
+            CssMixSpace::Xyz => xyz_to_rgb([x, y, z]),
+            CssMixSpace::Hsl => {
+                let (s, l) = (x.clamp(0.0, 1.0), y.clamp(0.0, 1.0));
+                let v = l + s * l.min(1.0 - l);
+                let s = if v <= 0.0 { 0.0 } else { 2.0 * (1.0 - l / v) };
+                hsv_to_rgb(Hsv { h: z, s, v })
+            }
+            CssMixSpace::Lch => lab_to_rgb(lch_to_lab(Lch { l: x, c: y, h: z })),
+            CssMixSpace::Oklch => oklab_to_rgb(oklch_to_oklab(Oklch { l: x, c: y, h: z })),
+        }
+    }
+}
+
+impl FromStr for CssMixSpace {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "srgb" => Ok(CssMixSpace::Srgb),
+            "srgb-linear" => Ok(CssMixSpace::SrgbLinear),
+            "lab" => Ok(CssMixSpace::Lab),
+            "oklab" => Ok(CssMixSpace::Oklab),
+            "xyz" | "xyz-d65" => Ok(CssMixSpace::Xyz),
+            "hsl" => Ok(CssMixSpace::Hsl),
+            "lch" => Ok(CssMixSpace::Lch),
+            "oklch" => Ok(CssMixSpace::Oklch),
+            _ => Err(format!(
+                "unknown color-mix() space `{}`, expected srgb, srgb-linear, lab, oklab, \
+                 xyz, hsl, lch or oklch",
+                s
+            )),
+        }
+    }
+}
+
+/// Normalizes the percentages of the two colors of `color-mix()`: a missing
+/// percentage is the rest of 100%, without either both are 50%. Returns the
+/// share of each color and the multiplier of the mixed alpha, below 1.0 if
+/// the percentages add up to less than 100%. `None` if a percentage is
+/// outside of `0.0..=100.0` or both are zero, which makes `color-mix()`
+/// invalid.
+pub fn normalize_percentages(p1: Option<f32>, p2: Option<f32>) -> Option<(f32, f32, f32)> {
+    let (p1, p2) = match (p1, p2) {
+        (None, None) => (50.0, 50.0),
+        (Some(p1), None) => (p1, 100.0 - p1),
+        (None, Some(p2)) => (100.0 - p2, p2),
+        (Some(p1), Some(p2)) => (p1, p2),
+    };
+    let sum = p1 + p2;
+    if !(0.0..=100.0).contains(&p1) || !(0.0..=100.0).contains(&p2) || sum <= 0.0 {
+        return None;
+    }
+    Some((p1 / sum, p2 / sum, (sum / 100.0).min(1.0)))
+}
+
+/// `color-mix(in <space> <path> hue, c1 p1%, c2 p2%)` of two colors with
+/// their alpha, returning the mixed color and alpha. `None` where
+/// `color-mix()` is invalid, see [`normalize_percentages`].
+pub fn color_mix(
+    space: CssMixSpace,
+    path: HuePath,
+    (c1, a1): (RGB, f32),
+    p1: Option<f32>,
+    (c2, a2): (RGB, f32),
+    p2: Option<f32>,
+) -> Option<(RGB, f32)> {
+    let (_, t, multiplier) = normalize_percentages(p1, p2)?;
+    let (color, alpha) = interpolate(space, path, (c1, a1), (c2, a2), t);
+    Some((color, alpha * multiplier))
+}
+
+/// Interpolates from `c1` (at `t = 0.0`) to `c2` (at `t = 1.0`) in `space`
+/// with premultiplied alpha.
+fn interpolate(
+    space: CssMixSpace,
+    path: HuePath,
+    (c1, a1): (RGB, f32),
+    (c2, a2): (RGB, f32),
+    t: f32,
+) -> (RGB, f32) {
+    let (from, from_hue) = space.components(c1);
+    let (to, to_hue) = space.components(c2);
+    let alpha = a1 + (a2 - a1) * t;
+    let premultiplied = |i: usize| {
+        let value = (from[i] * a1) + (to[i] * a2 - from[i] * a1) * t;
+        if alpha > 0.0 {
+            value / alpha
+        } else {
+            from[i] + (to[i] - from[i]) * t
+        }
+    };
+
+    let components = if space.is_polar() {
+        let hue = match (from_hue, to_hue) {
+            (true, true) => interpolate_hue(from[2], to[2], t, path),
+            (true, false) => from[2],
+            (false, true) => to[2],
+            (false, false) => 0.0,
+        };
+        [premultiplied(0), premultiplied(1), hue]
+    } else {
+        [premultiplied(0), premultiplied(1), premultiplied(2)]
+    };
+    (space.to_rgb(components), alpha)
+}
+
+/// The shares of the inputs as nested `color-mix()` calls: every step mixes
+/// the accumulated inputs with the next one by the ratio of their weights,
+/// so every input ends up with its share of the total weight. Steps where
+/// both weights are zero are skipped, `color-mix()` rejects them.
+fn steps(weights: &[f32]) -> Vec<Option<f32>> {
+    let mut accumulated = weights.first().copied().unwrap_or(0.0);
+    weights
+        .iter()
+        .skip(1)
+        .map(|w| {
+            let total = accumulated + w;
+            accumulated = total;
+            if total > 0.0 {
+                Some(w / total)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Mixes every input into one with nested `color-mix()`, see [`expression`].
+/// `None` if there are no inputs.
+pub fn mix(space: CssMixSpace, path: HuePath, input: &[RGB], weights: &[f32]) -> Option<RGB> {
+    let (first, rest) = input.split_first()?;
+    let mixed = rest
+        .iter()
+        .zip(steps(weights))
+        .fold((*first, 1.0), |acc, (color, t)| match t {
+            Some(t) => interpolate(space, path, acc, (*color, 1.0), t),
+            None => acc,
+        });
+    Some(mixed.0)
+}
+
+/// Formats a percentage of `color-mix()` with at most four decimals.
+fn percentage(share: f32) -> String {
+    let formatted = format!("{:.4}", share * 100.0);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    format!("{}%", trimmed)
+}
+
+/// The nested `color-mix()` expression a browser evaluates to the result of
+/// [`mix`], like `color-mix(in oklab, #ff0000 50%, #0000ff 50%)`.
+pub fn expression(space: CssMixSpace, path: HuePath, input: &[RGB], weights: &[f32]) -> String {
+    let method = if space.is_polar() && path != HuePath::Shorter {
+        format!("{} {} hue", space.name(), path.name())
+    } else {
+        space.name().to_string()
+    };
+    let (first, rest) = match input.split_first() {
+        Some(split) => split,
+        None => return String::new(),
+    };
+    rest.iter()
+        .zip(steps(weights))
+        .fold(to_hex(*first), |acc, (color, t)| match t {
+            Some(t) => format!(
+                "color-mix(in {}, {} {}, {} {})",
+                method,
+                acc,
+                percentage(1.0 - t),
+                to_hex(*color),
+                percentage(t)
+            ),
+            None => acc,
+        })
+}
@@ -0,0 +1,91 @@
+//! Color vision deficiency simulation, using the full severity matrices of
+//! Machado, Oliveira and Fernandes, "A Physiologically-based Model for
+//! Simulation of Color Vision Deficiency" (2009).
+
+use crate::convert::{linear_to_rgb, rgb_to_linear};
+use crate::delta_e::delta_e;
+use css_colors::RGB;
+use std::str::FromStr;
+
+/// A type of dichromacy to simulate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cvd {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Cvd {
+    fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            Cvd::Protanopia => [
+                [0.152_286, 1.052_583, -0.204_868],
+                [0.114_503, 0.786_281, 0.099_216],
+                [-0.003_882, -0.048_116, 1.051_998],
+            ],
+            Cvd::Deuteranopia => [
+                [0.367_322, 0.860_646, -0.227_968],
+                [0.280_085, 0.672_501, 0.047_413],
+                [-0.011_820, 0.042_940, 0.968_881],
+            ],
+            Cvd::Tritanopia => [
+                [1.255_528, -0.076_749, -0.178_779],
+                [-0.078_411, 0.930_809, 0.147_602],
+                [0.004_733, 0.691_367, 0.303_900],
+            ],
+        }
+    }
+}
+
+impl FromStr for Cvd {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "protanopia" => Ok(Cvd::Protanopia),
+            "deuteranopia" => Ok(Cvd::Deuteranopia),
+            "tritanopia" => Ok(Cvd::Tritanopia),
+            _ => Err(format!(
+                "unknown color vision deficiency `{}`, expected protanopia, deuteranopia or tritanopia",
+                s
+            )),
+        }
+    }
+}
+
+/// How `color` appears to someone with the given deficiency.
+pub fn simulate(color: RGB, cvd: Cvd) -> RGB {
+    let linear = rgb_to_linear(color);
+    let matrix = cvd.matrix();
+    let row = |r: [f32; 3]| r[0] * linear[0] + r[1] * linear[1] + r[2] * linear[2];
+    linear_to_rgb([row(matrix[0]), row(matrix[1]), row(matrix[2])])
+}
+
+/// The smallest ΔE2000 between any two of `colors` once simulated, i.e. how
+/// distinguishable the least distinguishable pair remains. `None` for fewer
+/// than two colors.
+pub fn min_pairwise_delta_e(colors: &[RGB], cvd: Cvd) -> Option<f32> {
+    let simulated: Vec<RGB> = colors.iter().map(|c| simulate(*c, cvd)).collect();
+    let mut min: Option<f32> = None;
+    for (i, a) in simulated.iter().enumerate() {
+        for b in &simulated[i + 1..] {
+            let delta = delta_e(*a, *b);
+            min = Some(min.map_or(delta, |m| m.min(delta)));
+        }
+    }
+    min
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use css_colors::rgb;
+
+    #[test]
+    fn red_and_green_are_hard_to_tell_apart_under_deuteranopia() {
+        let pair = [rgb(200, 60, 40), rgb(110, 130, 40)];
+        let seen = min_pairwise_delta_e(&pair, Cvd::Deuteranopia).unwrap();
+        assert!(seen < 10.0, "ΔE {}", seen);
+        assert!(delta_e(pair[0], pair[1]) > 3.0 * seen);
+    }
+}
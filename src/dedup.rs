@@ -0,0 +1,78 @@
+//! Collapsing near duplicate inputs before mixing with `--dedup`, so that a
+//! color given twice by accident does not count twice in the averages.
+
+use crate::delta_e::delta_e;
+use crate::output::to_hex;
+use css_colors::RGB;
+
+/// An input dropped as a near duplicate of an earlier input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Merge {
+    pub dropped: usize,
+    /// The input kept in its place, the closest one kept before it.
+    pub kept: usize,
+    pub delta_e: f32,
+}
+
+/// The inputs closer than `threshold` in CIEDE2000 to an input kept before
+/// them. Only colors are compared, not alphas. The first input is always
+/// kept.
+pub fn near_duplicates(input: &[RGB], threshold: f32) -> Vec<Merge> {
+    let mut kept: Vec<usize> = Vec::new();
+    let mut merges = Vec::new();
+    for (i, color) in input.iter().enumerate() {
+        let closest = kept
+            .iter()
+            .map(|&k| (k, delta_e(input[k], *color)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        match closest {
+            Some((k, distance)) if distance < threshold => merges.push(Merge {
+                dropped: i,
+                kept: k,
+                delta_e: distance,
+            }),
+            _ => kept.push(i),
+        }
+    }
+    merges
+}
+
+/// Removes the near duplicates of `input` together with their weights and
+/// alphas, keeping the weight of the input they collapse into as it is.
+/// Every merge is logged with the `id` of the record.
+pub fn dedup(
+    id: &str,
+    input: &mut Vec<RGB>,
+    weights: &mut Vec<f32>,
+    alphas: &mut Vec<f32>,
+    threshold: f32,
+) {
+    let merges = near_duplicates(input, threshold);
+    for merge in &merges {
+        log::warn!(
+            "{}: merged input {} ({}) into input {} ({}), ΔE {:.2}",
+            id,
+            merge.dropped + 1,
+            to_hex(input[merge.dropped]),
+            merge.kept + 1,
+            to_hex(input[merge.kept]),
+            merge.delta_e
+        );
+    }
+    let keep: Vec<bool> = (0..input.len())
+        .map(|i| !merges.iter().any(|m| m.dropped == i))
+        .collect();
+    retain(input, &keep);
+    retain(weights, &keep);
+    retain(alphas, &keep);
+}
+
+/// Keeps the `values` where `keep` is true.
+fn retain<T: Copy>(values: &mut Vec<T>, keep: &[bool]) {
+    *values = values
+        .iter()
+        .zip(keep)
+        .filter(|(_, keep)| **keep)
+        .map(|(value, _)| *value)
+        .collect();
+}
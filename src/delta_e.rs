@@ -0,0 +1,81 @@
+//! The CIEDE2000 color difference formula.
+//!
+//! See Sharma, Wu and Dalal, "The CIEDE2000 Color-Difference Formula:
+//! Implementation Notes, Supplementary Test Data, and Mathematical
+//! Observations" (2005).
+
+use crate::convert::{rgb_to_lab, Lab};
+use css_colors::RGB;
+
+/// Perceptual distance between two colors. Values below 1.0 are generally
+/// not noticeable.
+pub fn delta_e(lhs: RGB, rhs: RGB) -> f32 {
+    delta_e_2000(rgb_to_lab(lhs), rgb_to_lab(rhs))
+}
+
+/// ΔE*00 between two CIELAB colors with unit weighting factors.
+pub fn delta_e_2000(lhs: Lab, rhs: Lab) -> f32 {
+    let pow7_25 = 25f32.powi(7);
+
+    let c1 = lhs.a.hypot(lhs.b);
+    let c2 = rhs.a.hypot(rhs.b);
+    let c_bar = (c1 + c2) / 2.0;
+    let g = 0.5 * (1.0 - (c_bar.powi(7) / (c_bar.powi(7) + pow7_25)).sqrt());
+
+    let a1 = (1.0 + g) * lhs.a;
+    let a2 = (1.0 + g) * rhs.a;
+    let c1 = a1.hypot(lhs.b);
+    let c2 = a2.hypot(rhs.b);
+    let h1 = hue_degrees(lhs.b, a1);
+    let h2 = hue_degrees(rhs.b, a2);
+
+    let delta_l = rhs.l - lhs.l;
+    let delta_c = c2 - c1;
+    let delta_h = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 <= h1 {
+        h2 - h1 + 360.0
+    } else {
+        h2 - h1 - 360.0
+    };
+    let delta_h = 2.0 * (c1 * c2).sqrt() * (delta_h / 2.0).to_radians().sin();
+
+    let l_bar = (lhs.l + rhs.l) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+    let h_bar = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar).to_radians().cos()
+        + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+    let delta_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (c_bar.powi(7) / (c_bar.powi(7) + pow7_25)).sqrt();
+    let s_l = 1.0 + (0.015 * (l_bar - 50.0).powi(2)) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar;
+    let s_h = 1.0 + 0.015 * c_bar * t;
+    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let l_term = delta_l / s_l;
+    let c_term = delta_c / s_c;
+    let h_term = delta_h / s_h;
+
+    (l_term * l_term + c_term * c_term + h_term * h_term + r_t * c_term * h_term).sqrt()
+}
+
+fn hue_degrees(b: f32, a: f32) -> f32 {
+    if a == 0.0 && b == 0.0 {
+        0.0
+    } else {
+        b.atan2(a).to_degrees().rem_euclid(360.0)
+    }
+}
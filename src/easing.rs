@@ -0,0 +1,71 @@
+//! Easing functions for sweeping the mix of two colors from one to the
+//! other.
+
+use std::f32::consts::PI;
+use std::str::FromStr;
+
+/// How the share of the second color grows along an interpolation strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    /// Evenly spaced shares.
+    #[default]
+    Linear,
+    /// Sinusoidal, slow at both ends.
+    EaseInOut,
+    /// Cubic, slower at the ends and faster in the middle than `EaseInOut`.
+    Cubic,
+}
+
+impl Easing {
+    pub fn name(self) -> &'static str {
+        match self {
+            Easing::Linear => "linear",
+            Easing::EaseInOut => "ease-in-out",
+            Easing::Cubic => "cubic",
+        }
+    }
+
+    /// The eased share for a position `t` in `0.0..=1.0` along the strip.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => (1.0 - (PI * t).cos()) / 2.0,
+            Easing::Cubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(3) / 2.0
+                }
+            }
+        }
+    }
+
+    /// The shares of the second color at `steps` evenly spaced positions
+    /// from 0.0 to 1.0, both included.
+    pub fn sweep(self, steps: usize) -> Vec<f32> {
+        match steps {
+            0 => Vec::new(),
+            1 => vec![self.apply(0.5)],
+            _ => (0..steps)
+                .map(|k| self.apply(k as f32 / (steps - 1) as f32))
+                .collect(),
+        }
+    }
+}
+
+impl FromStr for Easing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(Easing::Linear),
+            "ease-in-out" => Ok(Easing::EaseInOut),
+            "cubic" => Ok(Easing::Cubic),
+            _ => Err(format!(
+                "unknown easing `{}`, expected linear, ease-in-out or cubic",
+                s
+            )),
+        }
+    }
+}
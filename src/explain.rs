@@ -0,0 +1,73 @@
+//! Human readable explanations of how strategies arrive at their results.
+
+use crate::convert::{rgb_to_lab, Lab};
+use crate::delta_e::delta_e;
+use crate::mixer::{self, Mixer};
+use crate::output::format_metric;
+use crate::{Options, Record};
+
+/// Like `format_metric`, but always with a sign.
+fn signed(value: f32, options: &Options) -> String {
+    format!("{:+.*}", options.precision, value)
+}
+
+/// Explains why the outputs of strategies `lhs` and `rhs` differ for
+/// `record`, by their working spaces and the CIELAB lightness, chroma and hue
+/// of `rhs` relative to `lhs`. `None` if either slug is not an output.
+pub fn explain_diff(
+    record: &Record,
+    lhs: &str,
+    rhs: &str,
+    mixers: &[Box<dyn Mixer>],
+    options: &Options,
+) -> Option<String> {
+    let (a, b) = (record.output(lhs)?, record.output(rhs)?);
+    let (space_a, space_b) = (mixer::describe(mixers, lhs)?, mixer::describe(mixers, rhs)?);
+    let (lab_a, lab_b) = (rgb_to_lab(a), rgb_to_lab(b));
+
+    let chroma = |l: Lab| l.a.hypot(l.b);
+    let hue = |l: Lab| l.b.atan2(l.a).to_degrees();
+    let delta_hue = (hue(lab_b) - hue(lab_a) + 180.0).rem_euclid(360.0) - 180.0;
+
+    Some(format!(
+        "{}: {} {}; {} {}, resulting in {} lightness, {} chroma, {}° hue (ΔE {})",
+        record.id,
+        lhs,
+        space_a,
+        rhs,
+        space_b,
+        signed(lab_b.l - lab_a.l, options),
+        signed(chroma(lab_b) - chroma(lab_a), options),
+        signed(delta_hue, options),
+        format_metric(delta_e(a, b), options),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::compute_record;
+    use css_colors::rgb;
+
+    #[test]
+    fn diff_of_gamma_and_linear_averages_reports_the_lightness() {
+        let options = Options::default();
+        let mixers = mixer::registry(&options);
+        let record = compute_record(
+            "1".to_string(),
+            vec![rgb(255, 0, 0), rgb(0, 255, 0)],
+            &mixers,
+        );
+        let explanation =
+            explain_diff(&record, "rgb-avg", "linear-avg", &mixers, &options).unwrap();
+
+        let lightness = |slug| rgb_to_lab(record.output(slug).unwrap()).l;
+        let delta = lightness("linear-avg") - lightness("rgb-avg");
+        // Averaging in linear light gives the brighter yellow.
+        assert!(delta > 0.0);
+        assert!(explanation.contains(&format!(
+            "resulting in {} lightness",
+            signed(delta, &options)
+        )));
+    }
+}
@@ -0,0 +1,688 @@
+//! Generating input palettes and computing records from them.
+
+use crate::convert::{hsl_to_rgb, oklab_in_gamut, oklab_to_rgb, oklch_to_oklab, Oklab, Oklch};
+use crate::dedup;
+use crate::easing::Easing;
+#[cfg(feature = "image-input")]
+use crate::image_input;
+use crate::input::{self, InputPalette};
+use crate::mixer::Mixer;
+use crate::{run_strategy, Failure, Options, Record};
+use css_colors::{Ratio, RGB};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::io;
+use std::iter;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A uniformly random opaque color.
+pub fn random_color(rng: &mut impl Rng) -> RGB {
+    RGB {
+        r: Ratio::from_u8(rng.gen()),
+        g: Ratio::from_u8(rng.gen()),
+        b: Ratio::from_u8(rng.gen()),
+    }
+}
+
+/// A named region of HSL random colors are drawn from, closer to real
+/// palettes than uniformly random RGB, which is mostly muddy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// Light, soft colors.
+    Pastel,
+    /// Saturated colors of medium lightness.
+    Vivid,
+    /// Deep, dark colors.
+    Dark,
+    /// Muted browns, ochres and olives.
+    Earth,
+}
+
+impl Preset {
+    /// The hue range in degrees of the preset, `None` for every hue.
+    pub fn hue_range(self) -> Option<(f32, f32)> {
+        match self {
+            Preset::Earth => Some((20.0, 70.0)),
+            _ => None,
+        }
+    }
+
+    /// The HSL saturation range in percent of the preset.
+    pub fn sat_range(self) -> (f32, f32) {
+        match self {
+            Preset::Pastel => (40.0, 80.0),
+            Preset::Vivid => (75.0, 100.0),
+            Preset::Dark => (20.0, 70.0),
+            Preset::Earth => (20.0, 55.0),
+        }
+    }
+
+    /// The HSL lightness range in percent of the preset.
+    pub fn light_range(self) -> (f32, f32) {
+        match self {
+            Preset::Pastel => (75.0, 90.0),
+            Preset::Vivid => (40.0, 60.0),
+            Preset::Dark => (8.0, 30.0),
+            Preset::Earth => (20.0, 60.0),
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pastel" => Ok(Preset::Pastel),
+            "vivid" => Ok(Preset::Vivid),
+            "dark" => Ok(Preset::Dark),
+            "earth" => Ok(Preset::Earth),
+            _ => Err(format!(
+                "unknown preset `{}`, expected pastel, vivid, dark or earth",
+                s
+            )),
+        }
+    }
+}
+
+/// The space random colors are drawn uniformly from, outside of a region
+/// of HSL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sampling {
+    /// Uniformly random sRGB bytes.
+    #[default]
+    Rgb,
+    /// Uniformly random points of OKLab inside of the sRGB gamut, a more
+    /// perceptually even spread.
+    Oklab,
+    /// Uniformly random OKLCH lightness, chroma and hue inside of the sRGB
+    /// gamut, evenly spread over hues.
+    Oklch,
+}
+
+impl FromStr for Sampling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rgb" => Ok(Sampling::Rgb),
+            "oklab" => Ok(Sampling::Oklab),
+            "oklch" => Ok(Sampling::Oklch),
+            _ => Err(format!(
+                "unknown sampling `{}`, expected rgb, oklab or oklch",
+                s
+            )),
+        }
+    }
+}
+
+/// A random opaque color uniformly drawn from OKLab or OKLCH, drawing again
+/// until it is inside of the sRGB gamut. The bounds are those of the gamut.
+fn draw_ok_color(rng: &mut impl Rng, sampling: Sampling) -> RGB {
+    loop {
+        let l = uniform(rng, 0.0, 1.0);
+        let color = match sampling {
+            Sampling::Oklch => oklch_to_oklab(Oklch {
+                l,
+                c: uniform(rng, 0.0, 0.33),
+                h: uniform(rng, 0.0, 360.0),
+            }),
+            _ => Oklab {
+                l,
+                a: uniform(rng, -0.24, 0.28),
+                b: uniform(rng, -0.32, 0.2),
+            },
+        };
+        if oklab_in_gamut(color) {
+            return oklab_to_rgb(color);
+        }
+    }
+}
+
+/// A random opaque color of a run, drawn uniformly from the space of
+/// `Options::sampling` unless the run is confined to a region of HSL with
+/// `Options::preset`, `hue_range`, `sat_range` or `light_range`. Then the
+/// hue, saturation and lightness are each uniformly random within their
+/// range, where the ranges given override those of the preset.
+pub fn draw_color(rng: &mut impl Rng, options: &Options) -> RGB {
+    if options.preset.is_none()
+        && options.hue_range.is_none()
+        && options.sat_range.is_none()
+        && options.light_range.is_none()
+    {
+        return match options.sampling {
+            Sampling::Rgb => random_color(rng),
+            sampling => draw_ok_color(rng, sampling),
+        };
+    }
+    let (hue_min, mut hue_max) = options
+        .hue_range
+        .or_else(|| options.preset.and_then(Preset::hue_range))
+        .unwrap_or((0.0, 360.0));
+    if hue_max < hue_min {
+        hue_max += 360.0;
+    }
+    let hue = uniform(rng, hue_min, hue_max);
+    draw_hsl(rng, hue, options, (0.0, 100.0), (0.0, 100.0))
+}
+
+/// The color of `hue` with a saturation and lightness uniformly random
+/// within the ranges of `options` or its preset, or else within
+/// `default_sat` and `default_light`.
+fn draw_hsl(
+    rng: &mut impl Rng,
+    hue: f32,
+    options: &Options,
+    default_sat: (f32, f32),
+    default_light: (f32, f32),
+) -> RGB {
+    let preset = options.preset;
+    let (sat_min, sat_max) = options
+        .sat_range
+        .or_else(|| preset.map(Preset::sat_range))
+        .unwrap_or(default_sat);
+    let (light_min, light_max) = options
+        .light_range
+        .or_else(|| preset.map(Preset::light_range))
+        .unwrap_or(default_light);
+    hsl_to_rgb(
+        hue,
+        uniform(rng, sat_min, sat_max) / 100.0,
+        uniform(rng, light_min, light_max) / 100.0,
+    )
+}
+
+/// The golden angle in degrees, the turn divided by the golden ratio
+/// squared.
+const GOLDEN_ANGLE: f32 = 137.507_76;
+
+/// The `len` random inputs of a palette, every one drawn with
+/// [`draw_color`] unless the run has `Options::golden_hues`.
+///
+/// Then the hues start at a random hue and step by the golden angle, the
+/// classic way to keep any number of hues well apart, each moved by up to
+/// the jitter in degrees either way. Saturations and lightnesses are drawn
+/// like in `draw_color`, from 60% to 80% and 45% to 65% without a range.
+pub fn draw_palette(rng: &mut impl Rng, len: usize, options: &Options) -> Vec<RGB> {
+    let jitter = match options.golden_hues {
+        Some(jitter) => jitter,
+        None => return (0..len).map(|_| draw_color(rng, options)).collect(),
+    };
+    let start = uniform(rng, 0.0, 360.0);
+    (0..len)
+        .map(|i| {
+            let hue = start + i as f32 * GOLDEN_ANGLE + uniform(rng, -jitter, jitter);
+            draw_hsl(
+                rng,
+                hue.rem_euclid(360.0),
+                options,
+                (60.0, 80.0),
+                (45.0, 65.0),
+            )
+        })
+        .collect()
+}
+
+/// A uniformly random number from `min` up to `max`, `min` if they are
+/// equal.
+fn uniform(rng: &mut impl Rng, min: f32, max: f32) -> f32 {
+    if max > min {
+        rng.gen_range(min, max)
+    } else {
+        min
+    }
+}
+
+/// The random number generator of a run, seeded with `Options::seed` or,
+/// without one, from entropy.
+pub fn rng(options: &Options) -> StdRng {
+    match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Every `(input count, round)` pair of a run.
+pub fn create_iter(
+    min_len: usize,
+    max_len: usize,
+    rounds: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    (min_len..=max_len).flat_map(move |input_len| iter::repeat(input_len).zip(0..rounds))
+}
+
+/// The record id for an input count and round.
+pub fn id(input_len: usize, round: usize) -> String {
+    format!("{}-{}", input_len, round)
+}
+
+/// Runs every mixer on `input` with equal weights, see
+/// [`compute_weighted_record`].
+pub fn compute_record(id: String, input: Vec<RGB>, mixers: &[Box<dyn Mixer>]) -> Record {
+    let weights = vec![1.0; input.len()];
+    compute_weighted_record(id, input, weights, mixers)
+}
+
+/// Runs every mixer on `input` weighted by `weights`, see
+/// [`compute_rgba_record`].
+pub fn compute_weighted_record(
+    id: String,
+    input: Vec<RGB>,
+    weights: Vec<f32>,
+    mixers: &[Box<dyn Mixer>],
+) -> Record {
+    let alphas = vec![1.0; input.len()];
+    compute_rgba_record(id, input, weights, alphas, mixers)
+}
+
+/// Runs every mixer on translucent `input` weighted by `weights`, see
+/// [`Mixer::mix_rgba`]. Mixers that fail are logged and fall back to black,
+/// they are listed in `Record::failures`.
+pub fn compute_rgba_record(
+    id: String,
+    input: Vec<RGB>,
+    weights: Vec<f32>,
+    alphas: Vec<f32>,
+    mixers: &[Box<dyn Mixer>],
+) -> Record {
+    let black = RGB {
+        r: Ratio::from_u8(0),
+        g: Ratio::from_u8(0),
+        b: Ratio::from_u8(0),
+    };
+    let mut outputs = Vec::with_capacity(mixers.len());
+    let mut failures = Vec::new();
+    for mixer in mixers {
+        let color = match run_strategy(mixer.slug(), || {
+            mixer
+                .mix_rgba(&input, &weights, &alphas)
+                .map(|(color, _)| color)
+        }) {
+            Ok(color) => color,
+            Err((slug, e)) => {
+                log::warn!("{} not computable for {:?}: {}", slug, &input, e);
+                failures.push(Failure {
+                    mixer: slug.to_string(),
+                    error: e.to_string(),
+                });
+                black
+            }
+        };
+        outputs.push((mixer.slug().to_string(), color));
+    }
+    Record {
+        id,
+        input,
+        weights,
+        alphas,
+        outputs,
+        failures,
+        label: None,
+        tags: Vec::new(),
+        source: None,
+        strips: Vec::new(),
+    }
+}
+
+/// The interpolation strip of every mixer from the first to the second of
+/// two opaque inputs, `steps` mixes with the share of the second growing
+/// by `easing`. Empty unless there are exactly two inputs, mixers failing
+/// at any step have no strip.
+pub fn strips(
+    input: &[RGB],
+    mixers: &[Box<dyn Mixer>],
+    steps: usize,
+    easing: Easing,
+) -> Vec<(String, Vec<RGB>)> {
+    if input.len() != 2 {
+        return Vec::new();
+    }
+    let shares = easing.sweep(steps);
+    mixers
+        .iter()
+        .filter_map(|mixer| {
+            let strip = shares
+                .iter()
+                .map(|t| mixer.mix_weighted(input, &[1.0 - t, *t]))
+                .collect::<Result<Vec<RGB>, _>>();
+            match strip {
+                Ok(strip) => Some((mixer.slug().to_string(), strip)),
+                Err(e) => {
+                    log::warn!("no strip of {} for {:?}: {}", mixer.slug(), input, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Adds the strips of `Options::strip_steps` to a computed record.
+fn with_strips(mut record: Record, mixers: &[Box<dyn Mixer>], options: &Options) -> Record {
+    if let Some(steps) = options.strip_steps {
+        record.strips = strips(&record.input, mixers, steps, options.easing);
+    }
+    record
+}
+
+/// Inputs drawn for a record, computed into one by [`compute_parallel`].
+struct Palette {
+    id: String,
+    input: Vec<RGB>,
+    weights: Vec<f32>,
+    alphas: Vec<f32>,
+    label: Option<String>,
+    tags: Vec<String>,
+    source: Option<String>,
+}
+
+impl Palette {
+    /// Equally weighted opaque inputs.
+    fn opaque(id: String, input: Vec<RGB>, source: Option<String>) -> Palette {
+        Palette {
+            id,
+            weights: vec![1.0; input.len()],
+            alphas: vec![1.0; input.len()],
+            input,
+            label: None,
+            tags: Vec::new(),
+            source,
+        }
+    }
+}
+
+/// Records computed at once by the worker threads. Palettes are drawn
+/// sequentially so a seed reproduces the run, only the mixing is parallel.
+const CHUNK_LEN: usize = 256;
+
+/// Computes `palettes` into records on the rayon thread pool, in chunks of
+/// `CHUNK_LEN` so records still stream, keeping the order of `palettes`.
+fn compute_parallel<'a>(
+    mut palettes: impl Iterator<Item = Palette> + 'a,
+    mixers: &'a [Box<dyn Mixer>],
+    options: &'a Options,
+) -> impl Iterator<Item = Record> + 'a {
+    iter::from_fn(move || {
+        let chunk: Vec<Palette> = palettes.by_ref().take(CHUNK_LEN).collect();
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    })
+    .flat_map(move |chunk| {
+        chunk
+            .into_par_iter()
+            .map(|mut palette| {
+                if let Some(threshold) = options.dedup {
+                    dedup::dedup(
+                        &palette.id,
+                        &mut palette.input,
+                        &mut palette.weights,
+                        &mut palette.alphas,
+                        threshold,
+                    );
+                }
+                let mut record = compute_rgba_record(
+                    palette.id,
+                    palette.input,
+                    palette.weights,
+                    palette.alphas,
+                    mixers,
+                );
+                record.label = palette.label;
+                record.tags = palette.tags;
+                record.source = palette.source;
+                with_strips(record, mixers, options)
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+/// The palettes read from `source`, in the order they were read.
+fn input_palettes(palettes: Vec<InputPalette>, source: String) -> Vec<Palette> {
+    palettes
+        .into_iter()
+        .map(|palette| Palette {
+            id: palette.name,
+            input: palette.colors,
+            weights: palette.weights,
+            alphas: palette.alphas,
+            label: palette.label,
+            tags: palette.tags,
+            source: Some(source.clone()),
+        })
+        .collect()
+}
+
+/// Where the palettes of a run come from, see [`input_source`].
+enum InputSource<'a> {
+    /// The input file of `--input`.
+    File(&'a Path),
+    /// The URL of `--from-url`.
+    #[cfg(feature = "from-url")]
+    Url(&'a str),
+    /// The directory of input files of `--input-dir`.
+    Dir(&'a Path),
+    /// The fixtures of `--fixtures`, drawn and written first if missing.
+    Fixtures(&'a Path),
+    /// Random colors or, with the `image-input` feature, pixels of `--image`.
+    Drawn,
+}
+
+/// The source of the palettes of a run, the first of `--input`,
+/// `--from-url`, `--input-dir` and `--fixtures` that is set.
+fn input_source(options: &Options) -> InputSource<'_> {
+    if let Some(path) = &options.input {
+        return InputSource::File(path);
+    }
+    #[cfg(feature = "from-url")]
+    {
+        if let Some(url) = &options.from_url {
+            return InputSource::Url(url);
+        }
+    }
+    if let Some(dir) = &options.input_dir {
+        return InputSource::Dir(dir);
+    }
+    if let Some(path) = &options.fixtures {
+        return InputSource::Fixtures(path);
+    }
+    InputSource::Drawn
+}
+
+/// The palettes of the [`input_source`], `None` if the palettes of the run
+/// are drawn instead. Missing fixtures are drawn and written first, then
+/// read back like existing ones, so the first run mixes exactly what every
+/// later run does.
+fn fixed_palettes(options: &Options) -> io::Result<Option<Vec<Palette>>> {
+    match input_source(options) {
+        InputSource::File(path) => {
+            let palettes = input::load(path, options.lenient)?;
+            Ok(Some(input_palettes(palettes, path.display().to_string())))
+        }
+        #[cfg(feature = "from-url")]
+        InputSource::Url(url) => {
+            let palettes = input::load_url(url, options.lenient)?;
+            Ok(Some(input_palettes(palettes, url.to_string())))
+        }
+        InputSource::Dir(dir) => {
+            let files = input::load_dir(dir, options.lenient)?;
+            log::info!("read {} input files from {}", files.len(), dir.display());
+            let palettes = files
+                .into_iter()
+                .flat_map(|(path, palettes)| input_palettes(palettes, path.display().to_string()))
+                .collect();
+            Ok(Some(palettes))
+        }
+        InputSource::Fixtures(path) => {
+            if !path.exists() {
+                let drawn: Vec<InputPalette> = drawn_palettes(options)?
+                    .map(|palette| InputPalette {
+                        name: palette.id,
+                        label: palette.label,
+                        tags: palette.tags,
+                        colors: palette.input,
+                        weights: palette.weights,
+                        alphas: palette.alphas,
+                    })
+                    .collect();
+                input::save(path, &drawn)?;
+                log::info!("wrote {} fixtures to {}", drawn.len(), path.display());
+            }
+            let palettes = input::load(path, options.lenient)?;
+            Ok(Some(input_palettes(palettes, path.display().to_string())))
+        }
+        InputSource::Drawn => Ok(None),
+    }
+}
+
+/// The palettes of a run drawn from random colors or, with the
+/// `image-input` feature, from pixels of `--image`.
+fn drawn_palettes<'a>(options: &'a Options) -> io::Result<Box<dyn Iterator<Item = Palette> + 'a>> {
+    let mut rng = rng(options);
+    #[cfg(feature = "image-input")]
+    {
+        if let Some(path) = &options.image {
+            let image = image_input::open(path)?;
+            if let Some((columns, rows)) = options.sample_grid {
+                let input = image_input::sample_grid(&image, columns, rows)?;
+                let id = format!("grid-{}x{}", columns, rows);
+                let source = format!("{}x{} grid of {}", columns, rows, path.display());
+                return Ok(Box::new(iter::once(Palette::opaque(
+                    id,
+                    input,
+                    Some(source),
+                ))));
+            }
+            let palettes = create_iter(options.min_len, options.max_len, options.rounds).map(
+                move |(input_len, round)| {
+                    Palette::opaque(
+                        id(input_len, round),
+                        image_input::sample_random(&image, input_len, &mut rng),
+                        Some(format!("pixels of {}", path.display())),
+                    )
+                },
+            );
+            return Ok(Box::new(palettes));
+        }
+    }
+
+    let palettes = create_iter(options.min_len, options.max_len, options.rounds).map(
+        move |(input_len, round)| {
+            let input = draw_palette(&mut rng, input_len, options);
+            Palette::opaque(id(input_len, round), input, None)
+        },
+    );
+    Ok(Box::new(palettes))
+}
+
+/// [`records`] together with [`max_input_len`], reading the input file only
+/// once.
+pub fn sized_records<'a>(
+    options: &'a Options,
+    mixers: &'a [Box<dyn Mixer>],
+) -> io::Result<(Box<dyn Iterator<Item = Record> + 'a>, usize)> {
+    if let Some(palettes) = fixed_palettes(options)? {
+        let max_len = palettes.iter().map(|p| p.input.len()).max().unwrap_or(0);
+        let records = compute_parallel(palettes.into_iter(), mixers, options);
+        return Ok((Box::new(records), max_len));
+    }
+    let records = compute_parallel(drawn_palettes(options)?, mixers, options);
+    Ok((Box::new(records), max_input_len(options)?))
+}
+
+/// Lazily generates the records of a run with `mixers`, one per palette of
+/// `--input` or `--fixtures`, or from random colors or, with the
+/// `image-input` feature, from pixels of `--image`. Fails upfront if the
+/// input file, fixtures or image cannot be read.
+pub fn records<'a>(
+    options: &'a Options,
+    mixers: &'a [Box<dyn Mixer>],
+) -> io::Result<Box<dyn Iterator<Item = Record> + 'a>> {
+    Ok(sized_records(options, mixers)?.0)
+}
+
+/// The largest number of inputs a record of `records` can have, taken from
+/// the same source the records are read from. Fails if the input file,
+/// URL, directory or existing fixtures cannot be read.
+pub fn max_input_len(options: &Options) -> io::Result<usize> {
+    let longest = |palettes: &[InputPalette]| palettes.iter().map(|p| p.colors.len()).max();
+    match input_source(options) {
+        InputSource::File(path) => Ok(longest(&input::load(path, options.lenient)?).unwrap_or(0)),
+        #[cfg(feature = "from-url")]
+        InputSource::Url(url) => Ok(longest(&input::load_url(url, options.lenient)?).unwrap_or(0)),
+        InputSource::Dir(dir) => {
+            let files = input::load_dir(dir, options.lenient)?;
+            Ok(files
+                .iter()
+                .filter_map(|(_, palettes)| longest(palettes))
+                .max()
+                .unwrap_or(0))
+        }
+        InputSource::Fixtures(path) if path.exists() => {
+            Ok(longest(&input::load(path, options.lenient)?).unwrap_or(0))
+        }
+        InputSource::Fixtures(_) | InputSource::Drawn => {
+            #[cfg(feature = "image-input")]
+            {
+                if let (Some(_), Some((columns, rows))) = (&options.image, options.sample_grid) {
+                    return Ok((columns * rows) as usize);
+                }
+            }
+            Ok(options.max_len)
+        }
+    }
+}
+
+/// Generates all records of a run with `mixers`, see `records`.
+pub fn generate_records(options: &Options, mixers: &[Box<dyn Mixer>]) -> io::Result<Vec<Record>> {
+    Ok(records(options, mixers)?.collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_grid_without_an_image_does_not_size_records() {
+        let options = Options {
+            sample_grid: Some((4, 3)),
+            ..Options::default()
+        };
+        assert_eq!(max_input_len(&options).unwrap(), options.max_len);
+    }
+
+    #[test]
+    fn input_dir_takes_precedence_over_fixtures_when_sizing_records() {
+        let dir = std::env::temp_dir().join(format!("color-mix-sources-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let palette = |name: &str, len: usize| InputPalette {
+            name: name.to_string(),
+            label: None,
+            tags: Vec::new(),
+            colors: vec![css_colors::rgb(10, 20, 30); len],
+            weights: vec![1.0; len],
+            alphas: vec![1.0; len],
+        };
+        let fixtures = dir.join("fixtures.json");
+        input::save(&fixtures, &[palette("short", 2)]).unwrap();
+        let input_dir = dir.join("inputs");
+        std::fs::create_dir_all(&input_dir).unwrap();
+        input::save(&input_dir.join("long.json"), &[palette("long", 5)]).unwrap();
+
+        let options = Options {
+            input_dir: Some(input_dir),
+            fixtures: Some(fixtures),
+            ..Options::default()
+        };
+        let len = max_input_len(&options);
+        let palettes = fixed_palettes(&options);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(len.unwrap(), 5);
+        let palettes = palettes.unwrap().unwrap();
+        assert!(palettes.iter().all(|palette| palette.input.len() == 5));
+    }
+}
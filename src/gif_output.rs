@@ -0,0 +1,74 @@
+//! An animated GIF of how the mix shifts as inputs are added, behind the
+//! `gif-output` feature.
+
+use crate::{compute_record, mixer, Options};
+use css_colors::RGB;
+use std::io;
+
+/// Edge length of a single swatch in pixels.
+const SWATCH: u16 = 48;
+
+fn fill(pixels: &mut [u8], width: u16, column: u16, row: u16, color: RGB) {
+    for y in row * SWATCH..(row + 1) * SWATCH {
+        for x in column * SWATCH..(column + 1) * SWATCH {
+            let i = (usize::from(y) * usize::from(width) + usize::from(x)) * 3;
+            pixels[i..i + 3].copy_from_slice(&[color.r.as_u8(), color.g.as_u8(), color.b.as_u8()]);
+        }
+    }
+}
+
+/// Encodes one frame per input of `palette`: frame `n` shows the first `n`
+/// inputs in the top row and every strategy's mix of them in the bottom row.
+pub fn animate(palette: &[RGB], options: &Options) -> io::Result<Vec<u8>> {
+    let mixers = mixer::registry(options);
+    let records: Vec<_> = (1..=palette.len())
+        .map(|n| compute_record(format!("gif-{}", n), palette[..n].to_vec(), &mixers))
+        .collect();
+    let outputs = records.first().map_or(0, |r| r.outputs.len());
+    let columns = palette.len().max(outputs) as u16;
+    let (width, height) = (columns * SWATCH, 2 * SWATCH);
+
+    let mut out = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut out, width, height, &[])
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        for record in &records {
+            let mut pixels = vec![255u8; usize::from(width) * usize::from(height) * 3];
+            for (column, input) in record.input.iter().enumerate() {
+                fill(&mut pixels, width, column as u16, 0, *input);
+            }
+            for (column, (_, output)) in record.outputs.iter().enumerate() {
+                fill(&mut pixels, width, column as u16, 1, *output);
+            }
+
+            let mut frame = gif::Frame::from_rgb_speed(width, height, &pixels, 10);
+            frame.delay = 100;
+            encoder
+                .write_frame(&frame)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use css_colors::rgb;
+
+    #[test]
+    fn gif_has_a_frame_per_input() {
+        let palette = [rgb(255, 0, 0), rgb(0, 255, 0), rgb(0, 0, 255)];
+        let gif = animate(&palette, &Options::default()).unwrap();
+        let mut decoder = gif::DecodeOptions::new().read_info(&gif[..]).unwrap();
+        let mut frames = 0;
+        while decoder.read_next_frame().unwrap().is_some() {
+            frames += 1;
+        }
+        assert_eq!(frames, palette.len());
+    }
+}
@@ -0,0 +1,140 @@
+//! Reading input colors from images, behind the `image-input` feature.
+
+use crate::space::{ColorSpace, OklabSpace};
+use crate::stats;
+use css_colors::{Ratio, RGB};
+use image::RgbImage;
+use rand::Rng;
+use std::io;
+use std::path::Path;
+
+/// Converts an `image` pixel into a `css_colors` color.
+pub fn from_image_rgb(pixel: image::Rgb<u8>) -> RGB {
+    let image::Rgb([r, g, b]) = pixel;
+    RGB {
+        r: Ratio::from_u8(r),
+        g: Ratio::from_u8(g),
+        b: Ratio::from_u8(b),
+    }
+}
+
+/// Converts a `css_colors` color into an `image` pixel.
+pub fn to_image_rgb(color: RGB) -> image::Rgb<u8> {
+    image::Rgb([color.r.as_u8(), color.g.as_u8(), color.b.as_u8()])
+}
+
+/// Loads an image from disk, discarding any alpha channel.
+pub fn open(path: &Path) -> io::Result<RgbImage> {
+    image::open(path)
+        .map(|image| image.to_rgb8())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Picks `count` pixels uniformly at random.
+pub fn sample_random(image: &RgbImage, count: usize, rng: &mut impl Rng) -> Vec<RGB> {
+    (0..count)
+        .map(|_| {
+            let x = rng.gen_range(0, image.width());
+            let y = rng.gen_range(0, image.height());
+            from_image_rgb(*image.get_pixel(x, y))
+        })
+        .collect()
+}
+
+/// Splits the image into `columns` x `rows` equally sized cells and returns
+/// the average color of each cell, row by row.
+pub fn sample_grid(image: &RgbImage, columns: u32, rows: u32) -> io::Result<Vec<RGB>> {
+    if columns == 0 || rows == 0 || columns > image.width() || rows > image.height() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "cannot sample a {}x{} grid from a {}x{} image",
+                columns,
+                rows,
+                image.width(),
+                image.height()
+            ),
+        ));
+    }
+
+    let mut samples = Vec::with_capacity((columns * rows) as usize);
+    for row in 0..rows {
+        let y_range = (row * image.height() / rows)..((row + 1) * image.height() / rows);
+        for column in 0..columns {
+            let x_range =
+                (column * image.width() / columns)..((column + 1) * image.width() / columns);
+
+            let mut sum = [0u64; 3];
+            let mut count = 0u64;
+            for y in y_range.clone() {
+                for x in x_range.clone() {
+                    let image::Rgb(pixel) = *image.get_pixel(x, y);
+                    for (s, p) in sum.iter_mut().zip(pixel.iter()) {
+                        *s += u64::from(*p);
+                    }
+                    count += 1;
+                }
+            }
+
+            let [r, g, b] = sum.map(|s| ((s + count / 2) / count) as u8);
+            samples.push(from_image_rgb(image::Rgb([r, g, b])));
+        }
+    }
+    Ok(samples)
+}
+
+/// The `count` most representative colors of the image with their share of
+/// the pixels, the largest first. Pixels are binned by the top five bits of
+/// every channel and the mean colors of the bins clustered with k-means in
+/// OKLab, so a photo needs no more work than a palette of 32768 colors.
+/// Fewer colors are returned if the image has fewer distinct ones.
+pub fn extract(image: &RgbImage, count: usize) -> Vec<(RGB, f32)> {
+    let mut bins = vec![([0u64; 3], 0u64); 1 << 15];
+    for &image::Rgb([r, g, b]) in image.pixels() {
+        let index = (usize::from(r >> 3) << 10) | (usize::from(g >> 3) << 5) | usize::from(b >> 3);
+        let (sum, pixels) = &mut bins[index];
+        for (s, p) in sum.iter_mut().zip([r, g, b].iter()) {
+            *s += u64::from(*p);
+        }
+        *pixels += 1;
+    }
+    let total = u64::from(image.width()) * u64::from(image.height());
+    let samples: Vec<([f32; 3], f32)> = bins
+        .into_iter()
+        .filter(|(_, pixels)| *pixels > 0)
+        .map(|(sum, pixels)| {
+            let [r, g, b] = sum.map(|s| ((s + pixels / 2) / pixels) as u8);
+            let color = from_image_rgb(image::Rgb([r, g, b]));
+            (
+                OklabSpace::to_components(color),
+                pixels as f32 / total as f32,
+            )
+        })
+        .collect();
+    let mut clusters = stats::kmeans(&samples, count.max(1));
+    clusters.sort_by(|a, b| b.1.total_cmp(&a.1));
+    clusters
+        .into_iter()
+        .map(|(centroid, share)| (OklabSpace::from_components(centroid), share))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_rgb_round_trips() {
+        let pixel = image::Rgb([12, 34, 56]);
+        assert_eq!(to_image_rgb(from_image_rgb(pixel)), pixel);
+    }
+
+    #[test]
+    fn sample_grid_of_a_solid_image_is_that_color() {
+        let pixel = image::Rgb([200, 120, 40]);
+        let image = RgbImage::from_pixel(13, 7, pixel);
+        let samples = sample_grid(&image, 4, 3).unwrap();
+        assert_eq!(samples.len(), 12);
+        assert!(samples.iter().all(|c| *c == from_image_rgb(pixel)));
+    }
+}
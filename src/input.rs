@@ -0,0 +1,702 @@
+//! Palettes read from a file with `--input` or `--fixtures` instead of
+//! random colors.
+//!
+//! The file is a JSON array of named palettes, every one mixed into one
+//! record:
+//!
+//! ```json
+//! [
+//!     {"name": "sunset", "colors": ["#ff8800", "tomato", "rgb(80 20 90)"]},
+//!     {
+//!         "name": "brand",
+//!         "colors": ["#123456", "rebeccapurple"],
+//!         "weights": [3, 1],
+//!         "label": "Brand colors",
+//!         "tags": ["2024"]
+//!     }
+//! ]
+//! ```
+//!
+//! Colors take every syntax of `mix`, including alpha. The name is the id of
+//! the record and has to be a unique slug, the label is shown instead if
+//! there is one.
+//!
+//! Files ending in `.csv` have one color per row instead, like a spreadsheet
+//! of brand colors:
+//!
+//! ```csv
+//! palette,color,weight
+//! brand,#123456,3
+//! brand,rebeccapurple,1
+//! accents,"rgb(255, 136, 0)",
+//! ```
+//!
+//! The `palette` and `weight` columns are optional, rows without a palette
+//! all belong to one named `palette` and empty weights are 1.0. Other
+//! columns are ignored. Without a header row the columns are color, palette
+//! and weight, in that order.
+//!
+//! Files ending in `.gpl` are GIMP palettes, mixed into one record named
+//! after the `Name:` of the palette. Files ending in `.ase` are Adobe swatch
+//! exchange files, every group of swatches is a palette named after the
+//! group and swatches outside of groups are one named `palette`. CMYK
+//! swatches are converted naively, without an ink profile.
+//!
+//! With `--from-url` and the `from-url` feature the palettes are downloaded
+//! instead, see `load_url`.
+
+use crate::convert::{cmyk_to_rgb, lab_d50_to_rgb, Cmyk, Lab};
+use crate::options::{check_slug, parse_color_alpha, parse_weighted_hex, slug, WeightedColor};
+use crate::output::{to_hex, to_hex_alpha};
+use css_colors::{Ratio, RGB};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+
+/// A palette read from an input file.
+#[derive(Debug, Clone)]
+pub struct InputPalette {
+    pub name: String,
+    pub label: Option<String>,
+    pub tags: Vec<String>,
+    pub colors: Vec<RGB>,
+    /// The weight of every color, 1.0 each if the file has none.
+    pub weights: Vec<f32>,
+    pub alphas: Vec<f32>,
+}
+
+/// The serialized form of an `InputPalette`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PaletteData {
+    name: String,
+    colors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    weights: Vec<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// Where in the file every color is, for errors. `color <n>` if empty.
+    #[serde(skip)]
+    positions: Vec<String>,
+}
+
+/// Fails with `error` or, if `lenient`, logs it and goes on.
+fn skip(lenient: bool, error: String) -> Result<(), String> {
+    if lenient {
+        log::warn!("skipped {}", error);
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+impl PaletteData {
+    /// Parses the colors, skipping those that cannot be parsed if
+    /// `lenient`.
+    fn parse(self, lenient: bool) -> Result<InputPalette, String> {
+        check_slug(&self.name)?;
+        let weights = if self.weights.is_empty() {
+            vec![1.0; self.colors.len()]
+        } else if self.weights.len() != self.colors.len() {
+            return Err(format!(
+                "`{}` has {} colors but {} weights",
+                self.name,
+                self.colors.len(),
+                self.weights.len()
+            ));
+        } else if self.weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err(format!(
+                "the weights of `{}` must not be negative",
+                self.name
+            ));
+        } else {
+            self.weights.clone()
+        };
+        let (mut colors, mut kept_weights, mut alphas) = (Vec::new(), Vec::new(), Vec::new());
+        for (i, (color, weight)) in self.colors.iter().zip(weights).enumerate() {
+            match parse_color_alpha(color) {
+                Ok((color, alpha)) => {
+                    colors.push(color);
+                    kept_weights.push(weight);
+                    alphas.push(alpha);
+                }
+                Err(e) => {
+                    let position = match self.positions.get(i) {
+                        Some(position) => position.clone(),
+                        None => format!("color {}", i + 1),
+                    };
+                    skip(lenient, format!("`{}` {}: {}", self.name, position, e))?;
+                }
+            }
+        }
+        if colors.is_empty() {
+            return Err(format!("`{}` has no colors", self.name));
+        }
+        Ok(InputPalette {
+            name: self.name,
+            label: self.label,
+            tags: self.tags,
+            colors,
+            weights: kept_weights,
+            alphas,
+        })
+    }
+}
+
+/// Parses the palettes of a JSON input file. If `lenient`, colors and
+/// palettes that cannot be parsed are skipped, here and in the other
+/// formats.
+pub fn parse(json: &str, lenient: bool) -> Result<Vec<InputPalette>, String> {
+    let data: Vec<PaletteData> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    validate(data, lenient)
+}
+
+/// Checks that the names of `data` are unique and parses every palette.
+fn validate(data: Vec<PaletteData>, lenient: bool) -> Result<Vec<InputPalette>, String> {
+    let mut names = HashSet::new();
+    let mut palettes = Vec::new();
+    for palette in data {
+        if !names.insert(palette.name.clone()) {
+            skip(
+                lenient,
+                format!("the name `{}` is used twice", palette.name),
+            )?;
+            continue;
+        }
+        match palette.parse(lenient) {
+            Ok(palette) => palettes.push(palette),
+            Err(e) => skip(lenient, e)?,
+        }
+    }
+    if palettes.is_empty() {
+        return Err("there are no palettes".to_string());
+    }
+    Ok(palettes)
+}
+
+/// Splits a CSV row into its trimmed cells. Cells may be quoted to contain
+/// commas, `""` in a quoted cell is a quote.
+fn csv_cells(row: &str) -> Vec<String> {
+    let mut cells = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cells.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells.into_iter().map(|c| c.trim().to_string()).collect()
+}
+
+/// Parses the palettes of a CSV input file, in the order they first appear.
+pub fn parse_csv(csv: &str, lenient: bool) -> Result<Vec<InputPalette>, String> {
+    let mut rows = csv
+        .lines()
+        .enumerate()
+        .filter(|(_, row)| !row.trim().is_empty())
+        .map(|(n, row)| (n + 1, csv_cells(row)))
+        .peekable();
+    let column =
+        |header: &[String], name: &str| header.iter().position(|c| c.eq_ignore_ascii_case(name));
+    let (color, palette, weight) = match rows.peek() {
+        Some((_, header)) if column(header, "color").is_some() => {
+            let columns = (
+                column(header, "color").unwrap(),
+                column(header, "palette"),
+                column(header, "weight"),
+            );
+            rows.next();
+            columns
+        }
+        _ => (0, Some(1), Some(2)),
+    };
+
+    let mut data: Vec<PaletteData> = Vec::new();
+    for (n, cells) in rows {
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|i| cells.get(i))
+                .map(String::as_str)
+                .filter(|c| !c.is_empty())
+        };
+        let color = match cell(Some(color)) {
+            Some(color) => color,
+            None => {
+                skip(lenient, format!("row {} has no color", n))?;
+                continue;
+            }
+        };
+        let weight = match cell(weight).map(|w| (w, w.parse())) {
+            Some((_, Ok(weight))) => weight,
+            Some((w, Err(_))) => {
+                skip(
+                    lenient,
+                    format!("row {}: `{}` is not a weight like 3 or 0.5", n, w),
+                )?;
+                continue;
+            }
+            None => 1.0,
+        };
+        let name = cell(palette).unwrap_or("palette");
+        let index = match data.iter().position(|p| p.name == name) {
+            Some(index) => index,
+            None => {
+                data.push(PaletteData {
+                    name: name.to_string(),
+                    colors: Vec::new(),
+                    weights: Vec::new(),
+                    label: None,
+                    tags: Vec::new(),
+                    positions: Vec::new(),
+                });
+                data.len() - 1
+            }
+        };
+        data[index].colors.push(color.to_string());
+        data[index].weights.push(weight);
+        data[index].positions.push(format!("row {}", n));
+    }
+    validate(data, lenient)
+}
+
+/// Parses a GIMP palette into one palette. The name of the GIMP palette is
+/// the label, a slug of it the name.
+pub fn parse_gpl(gpl: &str, lenient: bool) -> Result<Vec<InputPalette>, String> {
+    let mut lines = gpl.lines().enumerate();
+    if lines.next().map(|(_, l)| l.trim()) != Some("GIMP Palette") {
+        return Err("the file does not start with `GIMP Palette`".to_string());
+    }
+    let mut label = None;
+    let mut colors = Vec::new();
+    for (n, line) in lines {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("Name:") {
+            label = Some(name.trim().to_string());
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') || line.starts_with("Columns:") {
+            continue;
+        }
+        let channels: Option<Vec<u8>> = line
+            .split_whitespace()
+            .take(3)
+            .map(|c| c.parse().ok())
+            .collect::<Option<_>>()
+            .filter(|c: &Vec<u8>| c.len() == 3);
+        let channels = match channels {
+            Some(channels) => channels,
+            None => {
+                let error = format!("line {}: `{}` is not a color like 255 136 0", n + 1, line);
+                skip(lenient, error)?;
+                continue;
+            }
+        };
+        colors.push(format!(
+            "#{:02x}{:02x}{:02x}",
+            channels[0], channels[1], channels[2]
+        ));
+    }
+    validate(
+        vec![PaletteData {
+            name: slug(label.as_deref().unwrap_or("")),
+            weights: Vec::new(),
+            colors,
+            label,
+            tags: Vec::new(),
+            positions: Vec::new(),
+        }],
+        lenient,
+    )
+}
+
+/// Reads the big endian fields of an ASE file.
+struct AseReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> AseReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < len {
+            return Err("the file ends in the middle of a block".to_string());
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        self.take(4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, String> {
+        let value = f32::from_bits(self.u32()?);
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(format!("`{}` is not a color component", value))
+        }
+    }
+
+    /// A UTF-16 string prefixed by its length and ending in a zero.
+    fn name(&mut self) -> Result<String, String> {
+        let len = usize::from(self.u16()?);
+        let units: Vec<u16> = (0..len).map(|_| self.u16()).collect::<Result<_, _>>()?;
+        let units = units.strip_suffix(&[0]).unwrap_or(&units);
+        Ok(String::from_utf16_lossy(units))
+    }
+
+    /// The color of a swatch in its color model.
+    fn color(&mut self) -> Result<RGB, String> {
+        let model = self.take(4)?;
+        let color = match model {
+            b"RGB " => {
+                let channel = |c: f32| Ratio::from_f32(c.clamp(0.0, 1.0));
+                let (r, g, b) = (self.f32()?, self.f32()?, self.f32()?);
+                RGB {
+                    r: channel(r),
+                    g: channel(g),
+                    b: channel(b),
+                }
+            }
+            b"CMYK" => cmyk_to_rgb(Cmyk {
+                c: self.f32()?,
+                m: self.f32()?,
+                y: self.f32()?,
+                k: self.f32()?,
+            }),
+            b"LAB " => lab_d50_to_rgb(Lab {
+                l: self.f32()? * 100.0,
+                a: self.f32()?,
+                b: self.f32()?,
+            }),
+            b"Gray" => {
+                let gray = Ratio::from_f32(self.f32()?.clamp(0.0, 1.0));
+                RGB {
+                    r: gray,
+                    g: gray,
+                    b: gray,
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "unknown color model `{}`",
+                    String::from_utf8_lossy(model)
+                ))
+            }
+        };
+        Ok(color)
+    }
+}
+
+/// Parses an Adobe swatch exchange file into a palette per group.
+pub fn parse_ase(ase: &[u8], lenient: bool) -> Result<Vec<InputPalette>, String> {
+    const GROUP_START: u16 = 0xc001;
+    const GROUP_END: u16 = 0xc002;
+    const SWATCH: u16 = 0x0001;
+
+    let mut file = AseReader { bytes: ase };
+    if file.take(4).ok() != Some(&b"ASEF"[..]) {
+        return Err("the file does not start with `ASEF`".to_string());
+    }
+    let _version = (file.u16()?, file.u16()?);
+    let blocks = file.u32()?;
+
+    let mut data: Vec<PaletteData> = Vec::new();
+    let mut group: Option<usize> = None;
+    let mut ungrouped: Option<usize> = None;
+    for _ in 0..blocks {
+        let kind = file.u16()?;
+        let len = file.u32()? as usize;
+        let mut block = AseReader {
+            bytes: file.take(len)?,
+        };
+        let palette = |data: &mut Vec<PaletteData>, label: Option<String>| {
+            let base = slug(label.as_deref().unwrap_or(""));
+            let mut name = base.clone();
+            let mut n = 1;
+            while data.iter().any(|p| p.name == name) {
+                n += 1;
+                name = format!("{}-{}", base, n);
+            }
+            data.push(PaletteData {
+                name,
+                colors: Vec::new(),
+                weights: Vec::new(),
+                label,
+                tags: Vec::new(),
+                positions: Vec::new(),
+            });
+            data.len() - 1
+        };
+        match kind {
+            GROUP_START => group = Some(palette(&mut data, Some(block.name()?))),
+            GROUP_END => group = None,
+            SWATCH => {
+                let name = block.name()?;
+                let color = match block.color() {
+                    Ok(color) => color,
+                    Err(e) => {
+                        skip(lenient, format!("swatch `{}`: {}", name, e))?;
+                        continue;
+                    }
+                };
+                let index = match group.or(ungrouped) {
+                    Some(index) => index,
+                    None => {
+                        let index = palette(&mut data, None);
+                        ungrouped = Some(index);
+                        index
+                    }
+                };
+                data[index].colors.push(to_hex(color));
+            }
+            _ => {}
+        }
+    }
+    data.retain(|p| !p.colors.is_empty());
+    validate(data, lenient)
+}
+
+/// Reads the palettes of the input file at `path`, by its extension a CSV
+/// file, a GIMP palette, an ASE file or JSON, see [`parse`] for `lenient`.
+pub fn load(path: &Path, lenient: bool) -> io::Result<Vec<InputPalette>> {
+    let bytes = fs::read(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase());
+    let text = || std::str::from_utf8(&bytes).map_err(|e| e.to_string());
+    let palettes = match extension.as_deref() {
+        Some("ase") => parse_ase(&bytes, lenient),
+        Some("csv") => text().and_then(|text| parse_csv(text, lenient)),
+        Some("gpl") => text().and_then(|text| parse_gpl(text, lenient)),
+        _ => text().and_then(|text| parse(text, lenient)),
+    };
+    palettes.map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), e),
+        )
+    })
+}
+
+/// Whether `path` has the extension of an input file [`load`] reads.
+fn is_input_file(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase());
+    matches!(extension.as_deref(), Some("json" | "csv" | "gpl" | "ase"))
+}
+
+/// Reads the palettes of every input file in `dir` in parallel, for
+/// `--input-dir`, in the order of the file names. Files of other types are
+/// ignored. The name of every palette is prefixed with a slug of its file
+/// name, like `brand-primary` for `primary` in `brand.json`, so they stay
+/// unique across files.
+pub fn load_dir(dir: &Path, lenient: bool) -> io::Result<Vec<(PathBuf, Vec<InputPalette>)>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", dir.display(), e)))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    paths.retain(|path| path.is_file() && is_input_file(path));
+    paths.sort();
+    let files: Vec<(PathBuf, Vec<InputPalette>)> = paths
+        .into_par_iter()
+        .map(|path| {
+            let prefix = slug(&path.file_stem().unwrap_or_default().to_string_lossy());
+            let mut palettes = load(&path, lenient)?;
+            for palette in &mut palettes {
+                palette.name = format!("{}-{}", prefix, palette.name);
+            }
+            Ok((path, palettes))
+        })
+        .collect::<io::Result<_>>()?;
+
+    let mut names = HashSet::new();
+    for (path, palettes) in &files {
+        for palette in palettes {
+            if !names.insert(palette.name.as_str()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{}: the name `{}` is used twice",
+                        path.display(),
+                        palette.name
+                    ),
+                ));
+            }
+        }
+    }
+    if names.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: there are no input files", dir.display()),
+        ));
+    }
+    Ok(files)
+}
+
+/// Downloads the body of `url`, following redirects. Statuses other than
+/// 2xx fail with the status.
+#[cfg(feature = "from-url")]
+fn fetch(url: &str) -> io::Result<Vec<u8>> {
+    let error = |e: ureq::Error| match e {
+        ureq::Error::StatusCode(status) => {
+            io::Error::other(format!("{}: the server answered with HTTP {}", url, status))
+        }
+        e => io::Error::other(format!("{}: {}", url, e)),
+    };
+    let mut response = ureq::get(url).call().map_err(error)?;
+    response.body_mut().read_to_vec().map_err(error)
+}
+
+/// Downloads the palettes at `url` over HTTP for `--from-url`, like a
+/// palette published by a palette site or a CI artifact.
+///
+/// A body starting with `[` is JSON, either an input file or a plain array
+/// of colors. Any other body is a list of colors like the one piped to
+/// `mix -`. A single palette is named after the last segment of the URL.
+#[cfg(feature = "from-url")]
+pub fn load_url(url: &str, lenient: bool) -> io::Result<Vec<InputPalette>> {
+    let body = fetch(url)?;
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", url, e));
+    let text = std::str::from_utf8(&body).map_err(|e| invalid(e.to_string()))?;
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let segment = path.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+    let name = slug(segment.split('.').next().unwrap_or(""));
+
+    let colors: Vec<WeightedColor> = if text.trim_start().starts_with('[') {
+        match serde_json::from_str::<Vec<String>>(text) {
+            Ok(colors) => {
+                let lines = colors.join("\n");
+                read_colors(lines.as_bytes(), lenient).map_err(|e| invalid(e.to_string()))?
+            }
+            Err(_) => return parse(text, lenient).map_err(invalid),
+        }
+    } else {
+        read_colors(text.as_bytes(), lenient).map_err(|e| invalid(e.to_string()))?
+    };
+    if colors.is_empty() {
+        return Err(invalid("there are no colors".to_string()));
+    }
+    Ok(vec![InputPalette {
+        name,
+        label: None,
+        tags: Vec::new(),
+        colors: colors.iter().map(|c| c.color).collect(),
+        weights: colors.iter().map(|c| c.weight).collect(),
+        alphas: colors.iter().map(|c| c.alpha).collect(),
+    }])
+}
+
+/// Writes `palettes` as a JSON input file to `path`, for `--fixtures`.
+/// Weights of 1.0 each are left out.
+pub fn save(path: &Path, palettes: &[InputPalette]) -> io::Result<()> {
+    let data: Vec<PaletteData> = palettes
+        .iter()
+        .map(|palette| PaletteData {
+            name: palette.name.clone(),
+            colors: palette
+                .colors
+                .iter()
+                .zip(&palette.alphas)
+                .map(|(color, alpha)| to_hex_alpha(*color, *alpha))
+                .collect(),
+            weights: if palette.weights.iter().all(|w| *w == 1.0) {
+                Vec::new()
+            } else {
+                palette.weights.clone()
+            },
+            label: palette.label.clone(),
+            tags: palette.tags.clone(),
+            positions: Vec::new(),
+        })
+        .collect();
+    let json = serde_json::to_string_pretty(&data)?;
+    fs::write(path, json + "\n")
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+}
+
+/// Reads colors like the arguments of `mix`, one per line, as piped to
+/// `mix -`. Blank lines and lines starting with `//` are skipped, as are
+/// colors that cannot be parsed if `lenient`.
+pub fn read_colors(reader: impl BufRead, lenient: bool) -> io::Result<Vec<WeightedColor>> {
+    let mut colors = Vec::new();
+    for (n, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        match parse_weighted_hex(line) {
+            Ok(color) => colors.push(color),
+            Err(e) => skip(lenient, format!("line {}: {}", n + 1, e))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        }
+    }
+    Ok(colors)
+}
+
+#[cfg(all(test, feature = "from-url"))]
+mod tests {
+    use super::*;
+    use css_colors::rgb;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Answers a single request with `response` on a local port, returning
+    /// the URL of a palette there.
+    fn serve_once(response: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request = io::BufReader::new(stream.try_clone().unwrap());
+            for line in request.lines() {
+                if line.unwrap().is_empty() {
+                    break;
+                }
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        format!("http://{}/warm.txt", addr)
+    }
+
+    #[test]
+    fn url_of_a_list_of_colors_is_a_palette() {
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Length: 16\r\nConnection: close\r\n\r\n#ff0000\n#ff8800\n",
+        );
+        let palettes = load_url(&url, false).unwrap();
+        assert_eq!(palettes.len(), 1);
+        assert_eq!(palettes[0].name, "warm");
+        assert_eq!(palettes[0].colors, vec![rgb(255, 0, 0), rgb(255, 136, 0)]);
+    }
+
+    #[test]
+    fn url_answered_with_an_error_status_fails() {
+        let url =
+            serve_once("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        assert_eq!(
+            load_url(&url, false).unwrap_err().to_string(),
+            format!("{}: the server answered with HTTP 404", url)
+        );
+    }
+}
@@ -0,0 +1,1114 @@
+//! Mixing several colors into one, with a range of strategies to compare.
+//!
+//! Strategies like [`rgb_avg`], [`linear_avg`], [`less_mix`] and [`hsl_geo`]
+//! take a slice of colors and return the mixed color, or a [`ComputeError`]
+//! if it cannot be computed. Each of them is also a [`mixer::Mixer`], and
+//! [`generate::compute_record`] runs a registry of mixers on one palette and
+//! collects the results into a [`Record`], which renders to CSS and HTML.
+//!
+//! ```
+//! use color_mix::{hsl_geo, rgb_avg};
+//! use css_colors::rgb;
+//!
+//! let colors = [rgb(255, 0, 0), rgb(0, 0, 255)];
+//! assert_eq!(rgb_avg(&colors).unwrap(), rgb(127, 0, 127));
+//! assert!(hsl_geo(&colors).is_ok());
+//! ```
+
+pub mod accumulator;
+pub mod alpha;
+pub mod blend;
+pub mod blend_mode;
+pub mod cam16;
+pub mod chroma;
+pub mod chromaticity;
+pub mod command_mixer;
+pub mod config;
+pub mod convert;
+pub mod css_color;
+pub mod css_mix;
+pub mod cvd;
+pub mod dedup;
+pub mod delta_e;
+pub mod easing;
+pub mod explain;
+pub mod generate;
+#[cfg(feature = "gif-output")]
+pub mod gif_output;
+#[cfg(feature = "image-input")]
+pub mod image_input;
+pub mod input;
+pub mod luminance;
+pub mod mixer;
+pub mod named;
+pub mod options;
+pub mod output;
+pub mod pigment;
+pub mod quantize;
+mod record;
+pub mod run;
+pub mod space;
+pub mod spectral;
+pub mod stats;
+pub mod temperature;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm_plugin;
+
+pub use accumulator::Accumulator;
+pub use generate::{
+    compute_record, compute_rgba_record, compute_weighted_record, generate_records,
+};
+pub use mixer::Mixer;
+pub use options::Options;
+pub use record::{palette_name, representation_error, Failure, Record};
+pub use run::{run, Run, RunConfig};
+
+use blend::{Achromatic, HuePath, HueVote, MixSpace};
+use blend_mode::BlendMode;
+use cam16::ViewingConditions;
+use convert::{cmyk_to_rgb, hsv_to_rgb, rgb_to_cmyk, rgb_to_hsv, Cmyk, Hsv};
+use convert::{lab_to_lch, lab_to_rgb, lch_to_lab, rgb_to_hsl, rgb_to_lab, Lch};
+use convert::{linear_to_rgb, rgb_to_linear};
+use convert::{oklab_to_oklch, oklab_to_rgb, oklch_to_oklab, rgb_to_oklab, Oklch};
+use css_colors::{Angle, Color, Ratio, HSL, RGB};
+use css_mix::CssMixSpace;
+use std::error::Error;
+use std::fmt;
+
+/// Why a strategy could not compute a color.
+#[derive(Debug)]
+pub enum ComputeError {
+    /// There were no input colors.
+    EmptyInput,
+    /// An averaged channel did not fit into a byte.
+    AverageOutOfRange,
+    /// An intermediate value was NaN or infinite.
+    NonFinite,
+    /// A mixing weight fell outside of `0.0..=1.0`.
+    PercentageOutOfRange,
+    /// An input weight was negative or not finite, all weights were zero, or
+    /// there were not as many weights as inputs.
+    InvalidWeights,
+    /// No input had a hue to average.
+    UndefinedHue,
+    /// An input alpha fell outside of `0.0..=1.0`, or there were not as
+    /// many alphas as inputs.
+    AlphaOutOfRange,
+    /// A plugin mixer failed, with the reason.
+    Plugin(String),
+}
+
+impl fmt::Display for ComputeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputeError::EmptyInput => write!(f, "there are no input colors"),
+            ComputeError::AverageOutOfRange => {
+                write!(f, "an averaged channel does not fit into a byte")
+            }
+            ComputeError::NonFinite => write!(f, "an intermediate value is not finite"),
+            ComputeError::PercentageOutOfRange => {
+                write!(f, "a mixing weight is outside of 0.0..=1.0")
+            }
+            ComputeError::InvalidWeights => write!(
+                f,
+                "the input weights are not one non-negative number per input with a positive sum"
+            ),
+            ComputeError::UndefinedHue => write!(f, "no input has a hue to average"),
+            ComputeError::AlphaOutOfRange => {
+                write!(
+                    f,
+                    "the input alphas are not one number in 0.0..=1.0 per input"
+                )
+            }
+            ComputeError::Plugin(reason) => write!(f, "the plugin failed: {}", reason),
+        }
+    }
+}
+
+impl Error for ComputeError {}
+
+/// Checks that `weights` holds a non-negative weight for every input, with
+/// a positive total.
+fn check_weights(input: &[RGB], weights: &[f32]) -> Result<(), ComputeError> {
+    if input.is_empty() {
+        return Err(ComputeError::EmptyInput);
+    }
+    let total: f32 = weights.iter().sum();
+    if weights.len() != input.len()
+        || weights.iter().any(|w| !w.is_finite() || *w < 0.0)
+        || !(total > 0.0 && total.is_finite())
+    {
+        return Err(ComputeError::InvalidWeights);
+    }
+    Ok(())
+}
+
+/// Averages the gamma encoded sRGB channels, truncating the result.
+pub fn rgb_avg(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    rgb_avg_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`rgb_avg`] with a weight per input.
+pub fn rgb_avg_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    space::blend_many_weighted(input, weights, space::SrgbSpace).ok_or(ComputeError::EmptyInput)
+}
+
+/// Averages the channels in linear light and encodes the result again, which
+/// avoids the darkening of `rgb_avg`.
+pub fn linear_avg(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    linear_avg_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`linear_avg`] with a weight per input.
+pub fn linear_avg_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    space::blend_many_weighted(input, weights, space::LinearSpace).ok_or(ComputeError::EmptyInput)
+}
+
+/// Averages the channels in linear light, weighing every input by its
+/// relative luminance so bright colors dominate the mix like they dominate
+/// the impression of a palette. Only black inputs mix to black.
+pub fn luminance_avg(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    luminance_avg_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`luminance_avg`] with a weight per input, multiplied with its
+/// luminance.
+pub fn luminance_avg_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let dominance: Vec<f32> = input
+        .iter()
+        .zip(weights)
+        .map(|(c, w)| luminance::relative_luminance(*c) * w)
+        .collect();
+    log::trace!("luminance_avg: dominance {:?}", dominance);
+    space::blend_many_weighted(input, &dominance, space::LinearSpace)
+        .or_else(|| space::blend_many_weighted(input, weights, space::LinearSpace))
+        .ok_or(ComputeError::EmptyInput)
+}
+
+/// Averages the tristimulus values in CIE XYZ, approximating additive mixing
+/// of light. XYZ is a linear transform of linear-light sRGB, so this
+/// matches `linear_avg` up to rounding.
+pub fn xyz_avg(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    xyz_avg_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`xyz_avg`] with a weight per input.
+pub fn xyz_avg_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    space::blend_many_weighted(input, weights, space::XyzSpace).ok_or(ComputeError::EmptyInput)
+}
+
+/// Averages luma and chroma in BT.709 Y'CbCr, on the studio range scale a
+/// video pipeline stores them on. Y'CbCr is a linear transform of gamma
+/// encoded sRGB, so this is `rgb_avg` but rounded instead of truncated.
+pub fn ycbcr_avg(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    ycbcr_avg_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`ycbcr_avg`] with a weight per input.
+pub fn ycbcr_avg_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    space::blend_many_weighted(input, weights, space::YcbcrSpace).ok_or(ComputeError::EmptyInput)
+}
+
+/// Averages the cone responses in LMS, the space the eye integrates light
+/// in. LMS is a linear transform of XYZ, so like `xyz_avg` this matches
+/// `linear_avg` up to rounding.
+pub fn lms_avg(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    lms_avg_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`lms_avg`] with a weight per input.
+pub fn lms_avg_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    space::blend_many_weighted(input, weights, space::LmsSpace).ok_or(ComputeError::EmptyInput)
+}
+
+/// Averages L*, a* and b* in CIELAB, a perceptually motivated baseline.
+pub fn lab_avg(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    lab_avg_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`lab_avg`] with a weight per input.
+pub fn lab_avg_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    space::blend_many_weighted(input, weights, space::LabSpace).ok_or(ComputeError::EmptyInput)
+}
+
+/// Averages L, a and b in OKLab, which keeps hue and lightness of
+/// perceptual mixes more even than CIELAB.
+pub fn oklab_avg(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    oklab_avg_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`oklab_avg`] with a weight per input.
+pub fn oklab_avg_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    space::blend_many_weighted(input, weights, space::OklabSpace).ok_or(ComputeError::EmptyInput)
+}
+
+/// Takes the geometric mean of every channel in linear light, like light
+/// passing through a stack of filters with the transmittance of each input.
+/// A channel any input lacks is absent from the result.
+pub fn geometric_mean(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    geometric_mean_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`geometric_mean`] with a weight per input, its exponent in the product.
+pub fn geometric_mean_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    space::reduce_per_component(input, weights, space::LinearSpace, stats::geometric_mean)
+        .ok_or(ComputeError::EmptyInput)
+}
+
+/// Mixes the inputs like paints, averaging the Kubelka-Munk `K/S` of every
+/// band of their pigments, see [`pigment`]. Darker than the additive
+/// averages, and blue and yellow mix to a green.
+pub fn kubelka_munk(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    kubelka_munk_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`kubelka_munk`] with a weight per input, the share of each paint.
+pub fn kubelka_munk_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let paints: Vec<spectral::Spectrum> = input.iter().map(|c| pigment::to_spectrum(*c)).collect();
+    let mixed = pigment::mix(&paints, weights).ok_or(ComputeError::EmptyInput)?;
+    log::trace!("kubelka_munk: reflectance {:?}", mixed);
+
+    if !mixed.iter().all(|r| r.is_finite()) {
+        return Err(ComputeError::NonFinite);
+    }
+    Ok(pigment::from_spectrum(&mixed))
+}
+
+/// Mixes the inputs as paints per wavelength band of reflectance spectra
+/// upsampled from them, see [`spectral`]. Like `kubelka_munk` but with the
+/// seven basis spectra of Smits instead of three pigments.
+pub fn spectral_mix(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    spectral_mix_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`spectral_mix`] with a weight per input, the share of each paint.
+pub fn spectral_mix_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let spectra: Vec<spectral::Spectrum> =
+        input.iter().map(|c| spectral::to_spectrum(*c)).collect();
+    let mixed = spectral::mix(&spectra, weights).ok_or(ComputeError::EmptyInput)?;
+    log::trace!("spectral_mix: reflectance {:?}", mixed);
+
+    if !mixed.iter().all(|r| r.is_finite()) {
+        return Err(ComputeError::NonFinite);
+    }
+    Ok(spectral::from_spectrum(&mixed))
+}
+
+/// Averages the inks of naive CMYK, as a print workflow would. Black ink is
+/// averaged apart from the colored inks, so dark inputs desaturate a mix
+/// rather than darkening its hue.
+pub fn cmyk_avg(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    cmyk_avg_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`cmyk_avg`] with a weight per input.
+pub fn cmyk_avg_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let total: f32 = weights.iter().sum();
+    let cmyk: Vec<Cmyk> = input.iter().map(|c| rgb_to_cmyk(*c)).collect();
+    let average = |ink: fn(&Cmyk) -> f32| {
+        cmyk.iter()
+            .zip(weights)
+            .map(|(c, w)| ink(c) * w)
+            .sum::<f32>()
+            / total
+    };
+    let mixed = Cmyk {
+        c: average(|c| c.c),
+        m: average(|c| c.m),
+        y: average(|c| c.y),
+        k: average(|c| c.k),
+    };
+    log::trace!("cmyk_avg: {:?}", mixed);
+    Ok(cmyk_to_rgb(mixed))
+}
+
+/// Adds the inputs up like overlapping stage lights or LEDs, summing their
+/// linear-light channels instead of averaging them, so mixes get brighter
+/// with every input. Sums beyond white are clipped per channel, or with
+/// `normalize` scaled down together until the brightest channel fits,
+/// which keeps the hue of the sum.
+pub fn additive_light(input: impl AsRef<[RGB]>, normalize: bool) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    additive_light_weighted(input, &vec![1.0; input.len()], normalize)
+}
+
+/// [`additive_light`] with a weight per input, the intensity of each light.
+pub fn additive_light_weighted(
+    input: &[RGB],
+    weights: &[f32],
+    normalize: bool,
+) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let mut sum = [0.0f32; 3];
+    for (color, w) in input.iter().zip(weights) {
+        for (total, channel) in sum.iter_mut().zip(rgb_to_linear(*color)) {
+            *total += channel * w;
+        }
+    }
+    let peak = sum.iter().copied().fold(0.0, f32::max);
+    if normalize && peak > 1.0 {
+        sum = sum.map(|channel| channel / peak);
+    }
+    log::trace!("additive_light: {:?}", sum);
+    Ok(linear_to_rgb(sum))
+}
+
+/// Stacks the inputs like colored gels or filters in front of one light:
+/// every linear-light channel is the product of the transmittances of the
+/// inputs, so mixes only get darker, the classic subtractive behavior.
+/// Unlike the `multiply` blend mode this multiplies light, not gamma
+/// encoded values.
+pub fn subtractive_filter(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    subtractive_filter_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`subtractive_filter`] with a weight per input, the thickness of each
+/// filter: by the Beer-Lambert law a filter twice as thick transmits the
+/// square.
+pub fn subtractive_filter_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let mut product = [1.0f32; 3];
+    for (color, w) in input.iter().zip(weights) {
+        for (total, channel) in product.iter_mut().zip(rgb_to_linear(*color)) {
+            *total *= channel.powf(*w);
+        }
+    }
+    log::trace!("subtractive_filter: {:?}", product);
+    Ok(linear_to_rgb(product))
+}
+
+/// Stacks the inputs like layers, each composited over the ones before it
+/// with the Porter-Duff "over" operator in gamma encoded sRGB, as browsers
+/// do. The first input is the opaque backdrop, layer `k` above it has the
+/// opacity `opacities[k - 1]`, the last opacity repeats for any further
+/// layers and without any opacities every layer is opaque. Unlike the averages, the result depends on the input order.
+pub fn composite_over(input: impl AsRef<[RGB]>, opacities: &[f32]) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    composite_over_weighted(input, &vec![1.0; input.len()], opacities)
+}
+
+/// [`composite_over`] with a weight per input, scaling the opacity of its
+/// layer up to full opacity.
+pub fn composite_over_weighted(
+    input: &[RGB],
+    weights: &[f32],
+    opacities: &[f32],
+) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let (first, rest) = input.split_first().ok_or(ComputeError::EmptyInput)?;
+    let last = opacities.last().unwrap_or(&1.0);
+
+    rest.iter()
+        .zip(&weights[1..])
+        .enumerate()
+        .try_fold(*first, |backdrop, (k, (layer, w))| {
+            let opacity = opacities.get(k).unwrap_or(last) * w;
+            log::trace!("composite_over: layer {} at opacity {}", k + 1, opacity);
+
+            if opacity.is_nan() || opacity < 0.0 {
+                return Err(ComputeError::PercentageOutOfRange);
+            }
+            Ok(blend::blend_two(
+                backdrop,
+                *layer,
+                opacity.min(1.0),
+                MixSpace::Srgb,
+            ))
+        })
+}
+
+/// Stacks the inputs like layers, blending each onto the ones before it with
+/// `mode`, e.g. a chain of multiplies. The first input is the backdrop.
+pub fn blend_modes(input: impl AsRef<[RGB]>, mode: BlendMode) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    blend_modes_weighted(input, &vec![1.0; input.len()], mode)
+}
+
+/// [`blend_modes`] with a weight per input, the opacity of its layer up to
+/// full opacity at 1.0.
+pub fn blend_modes_weighted(
+    input: &[RGB],
+    weights: &[f32],
+    mode: BlendMode,
+) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let (first, rest) = input.split_first().ok_or(ComputeError::EmptyInput)?;
+
+    Ok(rest
+        .iter()
+        .zip(&weights[1..])
+        .fold(*first, |backdrop, (layer, w)| {
+            let blended = mode.blend(backdrop, *layer);
+            log::trace!("blend_modes: {} gives {:?}", mode.name(), blended);
+            if *w >= 1.0 {
+                blended
+            } else {
+                blend::blend_two(backdrop, blended, *w, MixSpace::Srgb)
+            }
+        }))
+}
+
+/// Mixes the inputs like nested CSS `color-mix(in <space>, ...)` would in a
+/// browser, see [`css_mix`]. Polar spaces interpolate hues along `path`.
+pub fn css_color_mix(
+    input: impl AsRef<[RGB]>,
+    space: CssMixSpace,
+    path: HuePath,
+) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    css_color_mix_weighted(input, &vec![1.0; input.len()], space, path)
+}
+
+/// [`css_color_mix`] with a weight per input, its share in the percentages.
+pub fn css_color_mix_weighted(
+    input: &[RGB],
+    weights: &[f32],
+    space: CssMixSpace,
+    path: HuePath,
+) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    css_mix::mix(space, path, input, weights).ok_or(ComputeError::EmptyInput)
+}
+
+/// Takes the median of every gamma encoded sRGB channel, truncating the
+/// result. A single outlier cannot pull the mix away like it does an
+/// average, and with an odd number of inputs every channel is one of the
+/// inputs' own.
+pub fn median(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    median_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`median`] with a weight per input, taking the weighted median.
+pub fn median_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    space::reduce_per_component(input, weights, space::SrgbSpace, stats::weighted_median)
+        .ok_or(ComputeError::EmptyInput)
+}
+
+/// Clusters the inputs in OKLab with k-means into at most `k` clusters and
+/// returns the centroid of the heaviest one, the dominant color, rather
+/// than a compromise between all of them. The first cluster wins on ties,
+/// a `k` of 0 is taken as 1, which is the `oklab_avg`.
+pub fn kmeans_dominant(input: impl AsRef<[RGB]>, k: usize) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    kmeans_dominant_weighted(input, &vec![1.0; input.len()], k)
+}
+
+/// [`kmeans_dominant`] with a weight per input, its mass in the clusters.
+pub fn kmeans_dominant_weighted(
+    input: &[RGB],
+    weights: &[f32],
+    k: usize,
+) -> Result<RGB, ComputeError> {
+    use space::ColorSpace;
+
+    check_weights(input, weights)?;
+    let samples: Vec<([f32; 3], f32)> = input
+        .iter()
+        .map(|c| space::OklabSpace::to_components(*c))
+        .zip(weights.iter().copied())
+        .collect();
+    let clusters = stats::kmeans(&samples, k.max(1));
+    log::trace!("kmeans_dominant: {:?}", clusters);
+    let mut dominant: Option<([f32; 3], f32)> = None;
+    for cluster in clusters {
+        if dominant.is_none_or(|d| cluster.1 > d.1) {
+            dominant = Some(cluster);
+        }
+    }
+    dominant
+        .map(|(centroid, _)| space::OklabSpace::from_components(centroid))
+        .ok_or(ComputeError::EmptyInput)
+}
+
+/// Averages every gamma encoded sRGB channel after discarding the lowest
+/// and the highest `trim` of the inputs, truncating the result. `trim` is a
+/// fraction of the inputs per end in `0.0..0.5`, a trim of `0.0` is
+/// `rgb_avg` and the closer it gets to `0.5` the closer the result is to
+/// [`median`].
+pub fn trimmed_mean(input: impl AsRef<[RGB]>, trim: f32) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    trimmed_mean_weighted(input, &vec![1.0; input.len()], trim)
+}
+
+/// [`trimmed_mean`] with a weight per input, trimming `trim` of the total
+/// weight per end.
+pub fn trimmed_mean_weighted(
+    input: &[RGB],
+    weights: &[f32],
+    trim: f32,
+) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    if !(0.0..0.5).contains(&trim) {
+        return Err(ComputeError::PercentageOutOfRange);
+    }
+    space::reduce_per_component(input, weights, space::SrgbSpace, |samples| {
+        stats::trimmed_mean(samples, trim)
+    })
+    .ok_or(ComputeError::EmptyInput)
+}
+
+/// Folds the inputs pairwise, giving the accumulator a weight of `1/n` at
+/// every step. The pairwise blend happens in `space`. Later inputs weigh
+/// more than earlier ones, so the result depends on the input order, see
+/// [`progressive_mix`] for the fold without that bias.
+pub fn less_mix(input: impl AsRef<[RGB]>, space: MixSpace) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    less_mix_weighted(input, &vec![1.0; input.len()], space)
+}
+
+/// [`less_mix`] with a weight per input. The accumulator weight of a step
+/// shrinks as the weight of the folded in input grows, and is `1/n` for
+/// equal weights.
+pub fn less_mix_weighted(
+    input: &[RGB],
+    weights: &[f32],
+    space: MixSpace,
+) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let (first, rest) = input.split_first().ok_or(ComputeError::EmptyInput)?;
+    let total: f32 = weights.iter().sum();
+    let others = (input.len() - 1) as f32;
+
+    rest.iter()
+        .zip(&weights[1..])
+        .try_fold(*first, |acc, (c, w)| {
+            let percent = (total - w) / (others * total);
+            log::trace!("less_mix: accumulator weight {}", percent);
+
+            if !(0.0..=1.0).contains(&percent) {
+                return Err(ComputeError::PercentageOutOfRange);
+            }
+            Ok(blend::blend_two(acc, *c, 1.0 - percent, space))
+        })
+}
+
+/// Folds the inputs pairwise like [`less_mix`], but blends in the `k`-th
+/// input with a ratio of `1/k`, so every input contributes equally to the
+/// result. In a space where blending is linear that is the plain average.
+pub fn progressive_mix(input: impl AsRef<[RGB]>, space: MixSpace) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    progressive_mix_weighted(input, &vec![1.0; input.len()], space)
+}
+
+/// [`progressive_mix`] with a weight per input, blending in each input with
+/// the ratio of its weight to the weight accumulated so far.
+pub fn progressive_mix_weighted(
+    input: &[RGB],
+    weights: &[f32],
+    space: MixSpace,
+) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let (first, rest) = input.split_first().ok_or(ComputeError::EmptyInput)?;
+
+    let (mixed, _) =
+        rest.iter()
+            .zip(&weights[1..])
+            .fold((*first, weights[0]), |(acc, acc_weight), (c, w)| {
+                let acc_weight = acc_weight + w;
+                let t = if acc_weight > 0.0 {
+                    w / acc_weight
+                } else {
+                    0.0
+                };
+                log::trace!("progressive_mix: blend ratio {}", t);
+                (blend::blend_two(acc, *c, t, space), acc_weight)
+            });
+    Ok(mixed)
+}
+
+/// The weighted mean of `(hue in degrees, weight)` pairs as an angle on the
+/// circle, in `-180.0..=180.0`. `total` is the sum of the weights.
+fn circular_mean(
+    hues: impl Iterator<Item = (f32, f32)> + Clone,
+    total: f32,
+) -> Result<f32, ComputeError> {
+    let x_sum: f32 = hues
+        .clone()
+        .map(|(degrees, w)| degrees.to_radians().cos() * w)
+        .sum();
+    let y_sum: f32 = hues
+        .map(|(degrees, w)| degrees.to_radians().sin() * w)
+        .sum();
+
+    let x_avg = x_sum / total;
+    let y_avg = y_sum / total;
+
+    let degrees = f32::atan2(y_avg, x_avg).to_degrees();
+    log::trace!(
+        "circular_mean: hue vector ({}, {}) at {}°",
+        x_avg,
+        y_avg,
+        degrees
+    );
+
+    // A NaN would silently cast to 0°, so reject it explicitly.
+    if !degrees.is_finite() {
+        return Err(ComputeError::NonFinite);
+    }
+    Ok(degrees)
+}
+
+/// Averages saturation and lightness in HSL and takes the circular mean of
+/// the hues.
+pub fn hsl_geo(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    hsl_geo_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`hsl_geo`] with a weight per input, which also weighs the hue vectors.
+pub fn hsl_geo_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    hsl_geo_voted(input, weights, HueVote::Equal, Achromatic::Vote)
+}
+
+/// [`hsl_geo_weighted`] with the hue vectors also scaled by `vote`, so that
+/// grays and washed out inputs can be kept from pulling the hue.
+/// `achromatic` decides whether grays vote at all and what to do if no
+/// input has a hue.
+pub fn hsl_geo_voted(
+    input: &[RGB],
+    weights: &[f32],
+    vote: HueVote,
+    achromatic: Achromatic,
+) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let total: f32 = weights.iter().sum();
+    let weighted = || input.iter().map(|c| rgb_to_hsl(*c)).zip(weights);
+
+    // Summed in f64 so that truncating the average of many equally weighted
+    // inputs matches integer division.
+    let average = |channel: fn(&HSL) -> u8| {
+        let sum: f64 = weighted()
+            .map(|(c, w)| f64::from(channel(&c)) * f64::from(*w))
+            .sum();
+        (sum / f64::from(total)).trunc()
+    };
+    let s_avg = average(|c| c.s.as_u8());
+    let l_avg = average(|c| c.l.as_u8());
+    log::trace!("hsl_geo: average saturation {}, lightness {}", s_avg, l_avg);
+
+    if !(0.0..=f64::from(u8::MAX)).contains(&s_avg) {
+        return Err(ComputeError::AverageOutOfRange);
+    }
+    if !(0.0..=f64::from(u8::MAX)).contains(&l_avg) {
+        return Err(ComputeError::AverageOutOfRange);
+    }
+
+    let vote_of = |c: &HSL| {
+        if achromatic != Achromatic::Vote && c.s.as_u8() == 0 {
+            0.0
+        } else {
+            vote.of(c)
+        }
+    };
+    let votes = || weighted().map(|(c, w)| (f32::from(c.h.degrees()), vote_of(&c) * w));
+    let vote_total: f32 = votes().map(|(_, v)| v).sum();
+    let degrees = if vote == HueVote::Equal && achromatic == Achromatic::Vote {
+        circular_mean(votes(), total)?
+    } else if vote_total > 0.0 {
+        circular_mean(votes(), vote_total)?
+    } else {
+        log::trace!("hsl_geo: no input has a {} vote", vote.name());
+        match achromatic {
+            // Without any hue the average saturation is zero, any hue will do.
+            Achromatic::Vote | Achromatic::Skip => 0.0,
+            Achromatic::RgbAvg => return rgb_avg_weighted(input, weights),
+            Achromatic::Error => return Err(ComputeError::UndefinedHue),
+        }
+    };
+
+    // `rem_euclid` may round tiny negative angles up to exactly 360.0.
+    let hue = Angle::new(degrees.rem_euclid(360.0) as u16 % 360);
+
+    Ok(HSL {
+        h: hue,
+        s: Ratio::from_u8(s_avg as u8),
+        l: Ratio::from_u8(l_avg as u8),
+    }
+    .to_rgb())
+}
+
+/// Averages saturation and value in HSV and takes the circular mean of the
+/// hues, the HSV counterpart of `hsl_geo`.
+pub fn hsv_geo(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    hsv_geo_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`hsv_geo`] with a weight per input.
+pub fn hsv_geo_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let total: f32 = weights.iter().sum();
+    let hsv: Vec<Hsv> = input.iter().map(|c| rgb_to_hsv(*c)).collect();
+
+    let s = hsv.iter().zip(weights).map(|(c, w)| c.s * w).sum::<f32>() / total;
+    let v = hsv.iter().zip(weights).map(|(c, w)| c.v * w).sum::<f32>() / total;
+    let h = circular_mean(hsv.iter().zip(weights).map(|(c, w)| (c.h, *w)), total)?;
+    log::trace!("hsv_geo: saturation {}, value {}, hue {}°", s, v, h);
+
+    Ok(hsv_to_rgb(Hsv { h, s, v }))
+}
+
+/// Averages J', a' and b' in CAM16-UCS, the most perceptually uniform of the
+/// spaces, for colors seen under `conditions`.
+pub fn cam16_avg(
+    input: impl AsRef<[RGB]>,
+    conditions: &ViewingConditions,
+) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    cam16_avg_weighted(input, &vec![1.0; input.len()], conditions)
+}
+
+/// [`cam16_avg`] with a weight per input.
+pub fn cam16_avg_weighted(
+    input: &[RGB],
+    weights: &[f32],
+    conditions: &ViewingConditions,
+) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let total: f32 = weights.iter().sum();
+    let mut sum = [0.0f32; 3];
+    for (color, weight) in input.iter().zip(weights) {
+        let ucs = cam16::rgb_to_ucs(*color, conditions);
+        for (s, c) in sum.iter_mut().zip(ucs) {
+            *s += c * weight;
+        }
+    }
+    let average = sum.map(|s| s / total);
+    log::trace!("cam16_avg: {:?} in CAM16-UCS", average);
+
+    if !average.iter().all(|c| c.is_finite()) {
+        return Err(ComputeError::NonFinite);
+    }
+    Ok(cam16::ucs_to_rgb(average, conditions))
+}
+
+/// Averages lightness and chroma in CIE LCh(ab) and takes the circular mean
+/// of the hues, the polar counterpart of `lab_avg` like `hsl_geo` is for HSL.
+pub fn lch_avg(input: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    lch_avg_weighted(input, &vec![1.0; input.len()])
+}
+
+/// [`lch_avg`] with a weight per input.
+pub fn lch_avg_weighted(input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let total: f32 = weights.iter().sum();
+    let lch: Vec<Lch> = input.iter().map(|c| lab_to_lch(rgb_to_lab(*c))).collect();
+
+    let l = lch.iter().zip(weights).map(|(c, w)| c.l * w).sum::<f32>() / total;
+    let c = lch.iter().zip(weights).map(|(c, w)| c.c * w).sum::<f32>() / total;
+    let h = circular_mean(lch.iter().zip(weights).map(|(c, w)| (c.h, *w)), total)?;
+    log::trace!("lch_avg: lightness {}, chroma {}, hue {}°", l, c, h);
+
+    Ok(lab_to_rgb(lch_to_lab(Lch { l, c, h })))
+}
+
+/// OKLCH chroma below which a color counts as achromatic, its hue is then
+/// powerless and takes the hue of the color it is interpolated with.
+const OKLCH_ACHROMATIC: f32 = 1e-4;
+
+/// Interpolates the inputs into each other one by one in OKLCH, each with
+/// its share of the inputs so far, moving the hue along `path`. Lightness
+/// and chroma end up averaged, the hue depends on `path` and the input order.
+pub fn oklch_avg(input: impl AsRef<[RGB]>, path: HuePath) -> Result<RGB, ComputeError> {
+    let input = input.as_ref();
+    oklch_avg_weighted(input, &vec![1.0; input.len()], path)
+}
+
+/// [`oklch_avg`] with a weight per input.
+pub fn oklch_avg_weighted(
+    input: &[RGB],
+    weights: &[f32],
+    path: HuePath,
+) -> Result<RGB, ComputeError> {
+    check_weights(input, weights)?;
+    let mut colors = input
+        .iter()
+        .map(|c| oklab_to_oklch(rgb_to_oklab(*c)))
+        .zip(weights);
+    let (first, first_weight) = colors.next().ok_or(ComputeError::EmptyInput)?;
+
+    let (mut acc, mut acc_weight) = (first, *first_weight);
+    for (color, weight) in colors {
+        let total = acc_weight + weight;
+        if total <= 0.0 {
+            continue;
+        }
+        let t = weight / total;
+        let (from_h, to_h) = match (acc.c < OKLCH_ACHROMATIC, color.c < OKLCH_ACHROMATIC) {
+            (true, false) => (color.h, color.h),
+            (false, true) => (acc.h, acc.h),
+            _ => (acc.h, color.h),
+        };
+        acc = Oklch {
+            l: acc.l + (color.l - acc.l) * t,
+            c: acc.c + (color.c - acc.c) * t,
+            h: blend::interpolate_hue(from_h, to_h, t, path),
+        };
+        acc_weight = total;
+    }
+    log::trace!("oklch_avg: {:?} along the {} arc", acc, path.name());
+
+    if !(acc.l.is_finite() && acc.c.is_finite() && acc.h.is_finite()) {
+        return Err(ComputeError::NonFinite);
+    }
+    Ok(oklab_to_rgb(oklch_to_oklab(acc)))
+}
+
+/// A consensus of strategies, the average of the results that could be
+/// computed, see [`MeanOfStrategies`](crate::mixer::MeanOfStrategies).
+/// Unlike `rgb_avg` the channels are rounded, so a result that is off by
+/// one is outvoted by the others.
+pub fn mean_of_strategies(results: impl AsRef<[RGB]>) -> Result<RGB, ComputeError> {
+    let results = results.as_ref();
+    if results.is_empty() {
+        return Err(ComputeError::EmptyInput);
+    }
+    let mean = |channel: fn(&RGB) -> Ratio| {
+        let sum: u32 = results.iter().map(|c| u32::from(channel(c).as_u8())).sum();
+        Ratio::from_u8((sum as f32 / results.len() as f32).round() as u8)
+    };
+    Ok(RGB {
+        r: mean(|c| c.r),
+        g: mean(|c| c.g),
+        b: mean(|c| c.b),
+    })
+}
+
+/// Runs a single strategy, tagging any failure with the strategy's slug.
+pub fn run_strategy<F>(slug: &str, strategy: F) -> Result<RGB, (&str, ComputeError)>
+where
+    F: FnOnce() -> Result<RGB, ComputeError>,
+{
+    strategy().map_err(|e| (slug, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use css_colors::rgb;
+    use proptest::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn less_mix_of_one_input_is_that_input() {
+        let color = rgb(12, 34, 56);
+        for space in [
+            MixSpace::Srgb,
+            MixSpace::Linear,
+            MixSpace::Oklab,
+            MixSpace::Hsl,
+        ] {
+            assert_eq!(less_mix([color], space).unwrap(), color);
+        }
+    }
+
+    #[test]
+    fn less_mix_of_no_inputs_fails() {
+        let empty: [RGB; 0] = [];
+        assert!(matches!(
+            less_mix(empty, MixSpace::Srgb),
+            Err(ComputeError::EmptyInput)
+        ));
+    }
+
+    #[test]
+    fn run_strategy_tags_a_failure_with_its_slug() {
+        let result = run_strategy("hsl-geo", || Err(ComputeError::NonFinite));
+        assert!(matches!(result, Err(("hsl-geo", ComputeError::NonFinite))));
+    }
+
+    #[test]
+    fn kmeans_dominant_picks_the_first_of_tied_clusters() {
+        let (red, blue) = (rgb(255, 0, 0), rgb(0, 0, 255));
+        let dominant = kmeans_dominant([red, blue], 2).unwrap();
+        assert!(delta_e::delta_e(dominant, red) < 1.0);
+        let dominant = kmeans_dominant([blue, red], 2).unwrap();
+        assert!(delta_e::delta_e(dominant, blue) < 1.0);
+    }
+
+    /// The difference `a - b` of two hues in degrees, in `-180.0..180.0`.
+    fn hue_difference(a: f32, b: f32) -> f32 {
+        (a - b + 180.0).rem_euclid(360.0) - 180.0
+    }
+
+    /// Two to four colors as `[hue, x, y]`, with `x` and `y` in `ranges` and
+    /// the hues within 120° of each other so that their mean is well
+    /// defined, and an offset to rotate their hues by.
+    fn rotated_input(ranges: [(f32, f32); 2]) -> impl Strategy<Value = (Vec<[f32; 3]>, f32)> {
+        let color = (
+            0.0f32..120.0,
+            ranges[0].0..ranges[0].1,
+            ranges[1].0..ranges[1].1,
+        );
+        (
+            0.0f32..360.0,
+            prop::collection::vec(color, 2..5),
+            0.0f32..360.0,
+        )
+            .prop_map(|(base, input, offset)| {
+                let input = input
+                    .into_iter()
+                    .map(|(h, x, y)| [base + h, x, y])
+                    .collect();
+                (input, offset)
+            })
+    }
+
+    /// Checks that rotating the hue of every input by `offset` rotates the
+    /// hue of the mix by that offset and keeps its other two components.
+    /// Colors are `[hue, x, y]` in the polar space of `to_polar` and
+    /// `from_polar`.
+    fn check_mix_rotates_with_its_inputs(
+        mix: impl Fn(&[RGB]) -> Result<RGB, ComputeError>,
+        to_polar: impl Fn(RGB) -> [f32; 3],
+        from_polar: impl Fn([f32; 3]) -> RGB,
+        (input, offset): (Vec<[f32; 3]>, f32),
+        tolerance: [f32; 3],
+    ) -> Result<(), TestCaseError> {
+        let colors: Vec<RGB> = input.iter().map(|c| from_polar(*c)).collect();
+        let rotated: Vec<RGB> = input
+            .iter()
+            .map(|&[h, x, y]| from_polar([h + offset, x, y]))
+            .collect();
+        let mixed = to_polar(mix(&colors).unwrap());
+        let rotated = to_polar(mix(&rotated).unwrap());
+
+        let hue_error = hue_difference(rotated[0], mixed[0] + offset).abs();
+        prop_assert!(hue_error <= tolerance[0], "hue off by {}", hue_error);
+        for i in 1..3 {
+            let error = (rotated[i] - mixed[i]).abs();
+            prop_assert!(error <= tolerance[i], "component {} off by {}", i, error);
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn hsl_geo_rotates_with_its_inputs(input in rotated_input([(0.4, 1.0), (0.3, 0.7)])) {
+            check_mix_rotates_with_its_inputs(
+                |input| hsl_geo(input),
+                |c| {
+                    let hsl = rgb_to_hsl(c);
+                    [rgb_to_hsv(c).h, hsl.s.as_f32(), hsl.l.as_f32()]
+                },
+                |[h, s, l]| convert::hsl_to_rgb(h, s, l),
+                input,
+                [3.0, 0.03, 0.02],
+            )?;
+        }
+
+        #[test]
+        fn hsv_geo_rotates_with_its_inputs(input in rotated_input([(0.4, 1.0), (0.4, 1.0)])) {
+            check_mix_rotates_with_its_inputs(
+                |input| hsv_geo(input),
+                |c| {
+                    let hsv = rgb_to_hsv(c);
+                    [hsv.h, hsv.s, hsv.v]
+                },
+                |[h, s, v]| hsv_to_rgb(Hsv { h, s, v }),
+                input,
+                [3.0, 0.03, 0.02],
+            )?;
+        }
+
+        #[test]
+        fn oklch_avg_rotates_with_its_inputs(input in rotated_input([(0.6, 0.75), (0.05, 0.09)])) {
+            check_mix_rotates_with_its_inputs(
+                |input| oklch_avg(input, HuePath::Shorter),
+                |c| {
+                    let oklch = oklab_to_oklch(rgb_to_oklab(c));
+                    [oklch.h, oklch.l, oklch.c]
+                },
+                |[h, l, c]| oklab_to_rgb(oklch_to_oklab(Oklch { l, c, h })),
+                input,
+                [4.0, 0.01, 0.01],
+            )?;
+        }
+    }
+
+    #[test]
+    fn circular_mean_of_a_nan_hue_fails() {
+        let hues = [(f32::NAN, 1.0), (90.0, 1.0)];
+        assert!(matches!(
+            circular_mean(hues.iter().copied(), 2.0),
+            Err(ComputeError::NonFinite)
+        ));
+        assert!(matches!(
+            circular_mean([(90.0, 0.0)].iter().copied(), 0.0),
+            Err(ComputeError::NonFinite)
+        ));
+    }
+
+    #[test]
+    fn strategies_take_vecs_arrays_and_slices() {
+        let input = vec![rgb(255, 0, 0), rgb(0, 0, 255)];
+        let from_vec = rgb_avg(input.clone()).unwrap();
+        assert_eq!(rgb_avg([input[0], input[1]]).unwrap(), from_vec);
+        assert_eq!(rgb_avg(&input[..]).unwrap(), from_vec);
+        assert_eq!(rgb_avg(&input).unwrap(), from_vec);
+    }
+
+    #[test]
+    fn kubelka_munk_mixes_yellow_and_blue_to_green() {
+        let green = kubelka_munk([rgb(255, 255, 0), rgb(0, 0, 255)]).unwrap();
+        assert!(green.g.as_u8() > green.r.as_u8());
+        assert!(green.g.as_u8() > green.b.as_u8());
+    }
+
+    #[test]
+    fn kubelka_munk_of_identical_inputs_is_that_input() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut colors = vec![rgb(0, 0, 0), rgb(255, 255, 255), rgb(255, 0, 0)];
+        colors.extend((0..64).map(|_| generate::random_color(&mut rng)));
+        for color in colors {
+            assert_eq!(kubelka_munk([color, color]).unwrap(), color);
+        }
+    }
+
+    #[test]
+    fn composite_over_without_opacities_stacks_opaque_layers() {
+        let (red, blue) = (rgb(255, 0, 0), rgb(0, 0, 255));
+        assert_eq!(composite_over([red], &[]).unwrap(), red);
+        assert_eq!(composite_over([red, blue], &[]).unwrap(), blue);
+        assert_eq!(composite_over([red, blue], &[0.0]).unwrap(), red);
+    }
+
+    #[test]
+    fn ycbcr_avg_of_a_single_input_is_that_input() {
+        assert_eq!(ycbcr_avg([rgb(255, 0, 0)]).unwrap(), rgb(255, 0, 0));
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..64 {
+            let color = generate::random_color(&mut rng);
+            assert_eq!(ycbcr_avg([color]).unwrap(), color);
+        }
+    }
+}
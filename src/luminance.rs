@@ -0,0 +1,64 @@
+//! Relative luminance and luminance normalization of inputs.
+
+use crate::convert::{rgb_to_hsl, rgb_to_xyz};
+use css_colors::{Color, Ratio, HSL, RGB};
+
+/// WCAG relative luminance, the `Y` of CIE XYZ in `0.0..=1.0`.
+pub fn relative_luminance(color: RGB) -> f32 {
+    rgb_to_xyz(color)[1]
+}
+
+/// Changes the HSL lightness of `color`, keeping hue and saturation, to the
+/// value whose relative luminance is closest to `target`.
+pub fn with_luminance(color: RGB, target: f32) -> RGB {
+    let HSL { h, s, .. } = rgb_to_hsl(color);
+    (0..=255u8)
+        .map(|l| {
+            HSL {
+                h,
+                s,
+                l: Ratio::from_u8(l),
+            }
+            .to_rgb()
+        })
+        .min_by(|a, b| {
+            let da = (relative_luminance(*a) - target).abs();
+            let db = (relative_luminance(*b) - target).abs();
+            da.total_cmp(&db)
+        })
+        .unwrap_or(color)
+}
+
+/// The mean relative luminance of `input` weighted by `weights`. `None`
+/// without inputs or if the total weight is not positive.
+pub fn mean_luminance(input: &[RGB], weights: &[f32]) -> Option<f32> {
+    let total: f32 = weights.iter().sum();
+    if input.is_empty() || total <= 0.0 {
+        return None;
+    }
+    let luminance: f32 = input
+        .iter()
+        .zip(weights)
+        .map(|(c, w)| relative_luminance(*c) * w)
+        .sum();
+    Some(luminance / total)
+}
+
+/// Runs `strategy` on the inputs moved to their common mean luminance,
+/// weighted by `weights`, so the hue blend is not biased by brightness
+/// differences, then moves the result back to that mean luminance.
+pub fn with_normalized_luminance<F, E>(
+    input: &[RGB],
+    weights: &[f32],
+    strategy: F,
+) -> Result<RGB, E>
+where
+    F: FnOnce(&[RGB]) -> Result<RGB, E>,
+{
+    let target = match mean_luminance(input, weights) {
+        Some(target) => target,
+        None => return strategy(input),
+    };
+    let normalized: Vec<RGB> = input.iter().map(|c| with_luminance(*c, target)).collect();
+    strategy(&normalized).map(|result| with_luminance(result, target))
+}
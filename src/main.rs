@@ -1,291 +1,475 @@
-use css_colors::{Angle, Color, Ratio, HSL, RGB};
+#[cfg(feature = "serve")]
+mod serve;
+
+mod cli;
+
+use clap::Parser;
+#[cfg(feature = "image-input")]
+use cli::ExtractArgs;
+use cli::{Cli, Command, ConvertArgs, GenerateArgs, MixArgs, RenderCommandArgs};
+use color_mix::config::Config;
+use color_mix::convert::{
+    rgb_to_hsl, rgb_to_lab, rgb_to_linear, rgb_to_oklab, rgb_to_xy, rgb_to_xyz,
+};
+use color_mix::generate;
+#[cfg(feature = "gif-output")]
+use color_mix::gif_output;
+#[cfg(feature = "image-input")]
+use color_mix::image_input;
+use color_mix::mixer::Mixer;
+use color_mix::options::WeightedColor;
+use color_mix::output::{css_color, format_metric, to_hex, to_hex_alpha};
+use color_mix::run::{RunMetadata, RunWriter, Sink};
+use color_mix::{dedup, explain, mixer, Options, Record, Run};
+use css_colors::Color;
 use std::fs::{self, File};
-use std::io::Write;
+use std::io::{self, BufWriter, Write};
 use std::iter;
-use std::panic;
 use std::path::Path;
 
-#[derive(Debug)]
-enum ComputeError {
-    EmptyInput,
-    AverageOutOfRange,
-    AngleOutOfRange,
-    PercentageOutOfRange,
-    Panic,
-}
-
-#[derive(Debug)]
-struct Record {
-    id: String,
-    input: Vec<RGB>,
-    rgb_avg: RGB,
-    less_mix: RGB,
-    hsl_geo: RGB,
+fn main() -> io::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    let cli = Cli::parse();
+    let mut options = Options::default();
+    Config::load(cli.config.as_deref())?
+        .apply(&mut options)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    match &cli.command {
+        None => generate(&cli.generate, options),
+        Some(Command::Generate(args)) => generate(args, options),
+        Some(Command::Mix(args)) => mix(args, options),
+        Some(Command::Convert(args)) => convert(args, options),
+        Some(Command::Render(args)) => render_saved(args, options),
+        #[cfg(feature = "image-input")]
+        Some(Command::Extract(args)) => extract(args, options),
+    }
 }
 
-impl Record {
-    fn to_css(&self) -> String {
-        let input: String = self
-            .input
-            .iter()
-            .enumerate()
-            .map(|(n, c)| {
-                format!(
-                    ".record-{} .input-{} {{
-    background-color: {};
-}}\n",
-                    &self.id,
-                    n,
-                    c.to_css(),
-                )
-            })
-            .collect();
-        let rgb_avg = format!(
-            ".record-{} .rgb-avg {{
-    background-color: {};
-}}\n",
-            &self.id,
-            &self.rgb_avg.to_css(),
-        );
-        let less_mix = format!(
-            ".record-{} .less-mix {{
-    background-color: {};
-}}\n",
-            &self.id,
-            &self.less_mix.to_css(),
-        );
-        let hsl_geo = format!(
-            ".record-{} .hsl-geo {{
-    background-color: {};
-}}\n",
-            &self.id,
-            &self.hsl_geo.to_css(),
-        );
-        vec![input, rgb_avg, less_mix, hsl_geo]
-            .into_iter()
-            .collect()
+fn generate(args: &GenerateArgs, options: Options) -> io::Result<()> {
+    let mut options = args.options(options);
+    if options.seed.is_none() {
+        let seed = rand::random();
+        eprintln!("seed: {}", seed);
+        options.seed = Some(seed);
     }
-
-    fn to_html(&self) -> String {
-        let input: String = self
-            .input
-            .iter()
-            .enumerate()
-            .map(|(n, _)| format!("<div class='input input-{}'></div>\n", n))
-            .collect();
-        format!(
-            "<div class='record record-{}'>
-    <div class='inputs'>
-    {}
-    </div>
-    <div class='outputs'>
-        <div class='output rgb-avg'></div>
-        <div class='output less-mix'></div>
-        <div class='output hsl-geo'></div>
-    </div>
-</div>\n",
-            self.id, input
-        )
+    let mixers = mixer::registry(&options);
+    let metadata = RunMetadata::new(&mixers, &options);
+
+    #[cfg(feature = "gif-output")]
+    {
+        if let Some(path) = &options.gif {
+            let mut rng = generate::rng(&options);
+            let palette = generate::draw_palette(&mut rng, options.max_len, &options);
+            fs::write(path, gif_output::animate(&palette, &options)?)?;
+        }
     }
-}
 
-fn rgb_avg(input: &[RGB]) -> Result<RGB, ComputeError> {
-    if input.is_empty() {
-        return Err(ComputeError::EmptyInput);
-    }
+    let (records, max_input_len) = generate::sized_records(&options, &mixers)?;
+    let saved = match &options.save_json {
+        Some(path) => Some(RunWriter::new(
+            vec![Sink::Json(create(path)?)],
+            &metadata,
+            max_input_len,
+            &options,
+        )?),
+        None => None,
+    };
+    render(records, saved, &metadata, max_input_len, &mixers, &options)
+}
 
-    let r_sum: u64 = input.iter().map(|c| u64::from(c.r.as_u8())).sum();
-    let g_sum: u64 = input.iter().map(|c| u64::from(c.g.as_u8())).sum();
-    let b_sum: u64 = input.iter().map(|c| u64::from(c.b.as_u8())).sum();
+fn mix(args: &MixArgs, options: Options) -> io::Result<()> {
+    let options = args.options(options);
+    mix_colors(
+        &args.colors(options.lenient)?,
+        "mix",
+        None,
+        args.page,
+        &options,
+    )
+}
 
-    let r_avg: u64 = r_sum / input.len() as u64;
-    let g_avg: u64 = g_sum / input.len() as u64;
-    let b_avg: u64 = b_sum / input.len() as u64;
+#[cfg(feature = "image-input")]
+fn extract(args: &ExtractArgs, options: Options) -> io::Result<()> {
+    let options = args.options(options);
+    let image = image_input::open(&args.image)?;
+    let palette = image_input::extract(&image, args.colors.unwrap_or(5));
+    let colors: Vec<WeightedColor> = palette
+        .iter()
+        .map(|(color, share)| {
+            println!("input\t{}\t{:.1}%", to_hex(*color), share * 100.0);
+            WeightedColor {
+                color: *color,
+                alpha: 1.0,
+                weight: if args.weighted { *share } else { 1.0 },
+            }
+        })
+        .collect();
+    let source = format!("{} colors of {}", colors.len(), args.image.display());
+    mix_colors(&colors, "extract", Some(source), args.page, &options)
+}
 
-    if r_avg > u64::from(u8::max_value()) {
-        return Err(ComputeError::AverageOutOfRange);
+/// Prints the result of every mixer for `colors` and, with `page`, renders
+/// them as a record with `id` and `source`.
+fn mix_colors(
+    colors: &[WeightedColor],
+    id: &str,
+    source: Option<String>,
+    page: bool,
+    options: &Options,
+) -> io::Result<()> {
+    let mut input: Vec<_> = colors.iter().map(|c| c.color).collect();
+    let mut weights: Vec<_> = colors.iter().map(|c| c.weight).collect();
+    let mut alphas: Vec<_> = colors.iter().map(|c| c.alpha).collect();
+    if let Some(threshold) = options.dedup {
+        dedup::dedup(id, &mut input, &mut weights, &mut alphas, threshold);
     }
-    if g_avg > u64::from(u8::max_value()) {
-        return Err(ComputeError::AverageOutOfRange);
-    }
-    if b_avg > u64::from(u8::max_value()) {
-        return Err(ComputeError::AverageOutOfRange);
+    let mixers = mixer::registry(options);
+    let strips = match options.strip_steps {
+        Some(steps) => {
+            if input.len() != 2 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--strip mixes exactly two colors",
+                ));
+            }
+            let strips = generate::strips(&input, &mixers, steps, options.easing);
+            for (slug, strip) in &strips {
+                let colors: Vec<String> = strip.iter().map(|c| to_hex(*c)).collect();
+                println!("{}\t{}", slug, colors.join(" "));
+            }
+            strips
+        }
+        None => {
+            let mut failed = 0;
+            for mixer in &mixers {
+                match mixer.mix_rgba(&input, &weights, &alphas) {
+                    Ok((color, alpha)) => {
+                        println!("{}\t{}", mixer.slug(), to_hex_alpha(color, alpha))
+                    }
+                    Err(e) => {
+                        eprintln!("{}\t{}", mixer.slug(), e);
+                        failed += 1;
+                    }
+                }
+            }
+            if options.strict && failed > 0 {
+                return Err(io::Error::other(format!("{} mixers failed", failed)));
+            }
+            Vec::new()
+        }
+    };
+    if page {
+        let mut record =
+            generate::compute_rgba_record(id.to_string(), input, weights, alphas, &mixers);
+        record.strips = strips;
+        record.source = source;
+        let metadata = RunMetadata::new(&mixers, options);
+        let max_input_len = record.input.len();
+        render(
+            iter::once(record),
+            None,
+            &metadata,
+            max_input_len,
+            &mixers,
+            options,
+        )?;
     }
-
-    Ok(RGB {
-        r: Ratio::from_u8(r_avg as u8),
-        g: Ratio::from_u8(g_avg as u8),
-        b: Ratio::from_u8(b_avg as u8),
-    })
+    Ok(())
 }
 
-fn less_mix(input: &[RGB]) -> Result<RGB, ComputeError> {
-    if input.is_empty() {
-        return Err(ComputeError::EmptyInput);
-    }
-
-    let percent = dbg!(1.0 / input.len() as f32);
-
-    if percent < 0.0 || percent > 1.0 {
-        return Err(ComputeError::PercentageOutOfRange);
-    }
+fn convert(args: &ConvertArgs, options: Options) -> io::Result<()> {
+    let options = args.options(options);
+    let color = args.color;
+    let components = |values: &[f32]| {
+        values
+            .iter()
+            .map(|v| format_metric(*v, &options))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let lab = rgb_to_lab(color);
+    let oklab = rgb_to_oklab(color);
+    println!("hex\t{}", to_hex(color));
+    println!("rgb\t{}", css_color(color, &options));
+    println!("hsl\t{}", rgb_to_hsl(color).to_css());
+    println!("linear\t{}", components(&rgb_to_linear(color)));
+    println!("xyz\t{}", components(&rgb_to_xyz(color)));
+    println!("xy\t{}", components(&rgb_to_xy(color)));
+    println!("lab\t{}", components(&[lab.l, lab.a, lab.b]));
+    println!("oklab\t{}", components(&[oklab.l, oklab.a, oklab.b]));
+    Ok(())
+}
 
-    let ratio = Ratio::from_f32(percent);
+fn render_saved(args: &RenderCommandArgs, options: Options) -> io::Result<()> {
+    let options = args.options(options);
+    let run = Run::from_json(&fs::read_to_string(&args.run)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let max_input_len = run.records.iter().map(|r| r.input.len()).max().unwrap_or(0);
+    render(
+        run.records.into_iter(),
+        None,
+        &run.metadata,
+        max_input_len,
+        &mixer::registry(&options),
+        &options,
+    )
+}
 
-    Ok(input
-        .iter()
-        .skip(1)
-        .fold(input[0], |acc, c| acc.mix(c.clone(), ratio).to_rgb()))
+fn create(path: &Path) -> io::Result<Box<dyn Write>> {
+    Ok(Box::new(BufWriter::new(File::create(path)?)))
 }
 
-fn hsl_geo(input: &[RGB]) -> Result<RGB, ComputeError> {
-    if input.is_empty() {
-        return Err(ComputeError::EmptyInput);
+/// Reports every failed mixer of `record` for `--strict`.
+fn report_failures(record: &Record) {
+    let input: Vec<String> = record.input.iter().map(|c| to_hex(*c)).collect();
+    eprintln!("record {} ({}):", record.id, input.join(", "));
+    for failure in &record.failures {
+        eprintln!("    {}: {}", failure.mixer, failure.error);
     }
+}
 
-    let s_sum: u64 = input
-        .iter()
-        .map(|c| u64::from(c.clone().to_hsl().s.as_u8()))
-        .sum();
-    let l_sum: u64 = input
-        .iter()
-        .map(|c| u64::from(c.clone().to_hsl().l.as_u8()))
-        .sum();
-
-    let s_avg: u64 = dbg!(s_sum / input.len() as u64);
-    let l_avg: u64 = dbg!(l_sum / input.len() as u64);
+/// Streams `records` to the page and the other requested outputs, or serves
+/// them with `--serve`. Every record is also written to `saved`, ignoring
+/// `--only-failures`.
+///
+/// With `--strict`, failed records are reported as they come and the run
+/// fails once all records are written.
+fn render(
+    records: impl Iterator<Item = Record>,
+    saved: Option<RunWriter>,
+    metadata: &RunMetadata,
+    max_input_len: usize,
+    mixers: &[Box<dyn Mixer>],
+    options: &Options,
+) -> io::Result<()> {
+    let mut served = Vec::new();
+    let mut page = if serving(options) {
+        None
+    } else {
+        Some(page_writer(metadata, max_input_len, options)?)
+    };
+
+    let mut saved = saved;
+    let (mut total, mut failed) = (0, 0);
+    for record in records {
+        total += 1;
+        if let Some(saved) = &mut saved {
+            saved.write(&record)?;
+        }
+        if !record.failures.is_empty() {
+            failed += 1;
+            if options.strict {
+                report_failures(&record);
+            }
+        } else if options.only_failures {
+            continue;
+        }
+
+        if let Some((lhs, rhs)) = &options.explain_diff {
+            match explain::explain_diff(&record, lhs, rhs, mixers, options) {
+                Some(explanation) => println!("{}", explanation),
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("--explain-diff: unknown strategy in `{},{}`", lhs, rhs),
+                    ))
+                }
+            }
+        }
 
-    if s_avg > u64::from(u8::max_value()) {
-        return Err(ComputeError::AverageOutOfRange);
+        match &mut page {
+            Some(page) => page.write(&record)?,
+            None => served.push(record),
+        }
     }
-    if l_avg > u64::from(u8::max_value()) {
-        return Err(ComputeError::AverageOutOfRange);
+    if let Some(saved) = saved {
+        saved.finish()?;
     }
-
-    let x_sum: f32 = input
-        .iter()
-        .map(|c| f32::from(c.clone().to_hsl().h.degrees()))
-        .map(|degrees| degrees.to_radians().cos())
-        .sum();
-    let y_sum: f32 = input
-        .iter()
-        .map(|c| f32::from(c.clone().to_hsl().h.degrees()))
-        .map(|degrees| degrees.to_radians().sin())
-        .sum();
-
-    let x_avg = dbg!(x_sum / input.len() as f32);
-    let y_avg = dbg!(y_sum / input.len() as f32);
-
-    let mut angle = dbg!(f32::atan2(y_avg, x_avg).to_degrees() as i16);
-
-    while angle < 0 {
-        angle += 360;
+    if let Some(page) = page {
+        page.finish()?;
     }
 
-    if angle > 360 || angle < 0 {
-        return Err(ComputeError::AngleOutOfRange);
+    if options.strict && failed > 0 {
+        return Err(io::Error::other(format!(
+            "--strict: {} of {} records failed",
+            failed, total
+        )));
     }
 
-    let hue = Angle::new(angle as u16);
-
-    Ok(HSL {
-        h: hue,
-        s: Ratio::from_u8(s_avg as u8),
-        l: Ratio::from_u8(l_avg as u8),
+    #[cfg(feature = "serve")]
+    {
+        if let Some(addr) = &options.serve {
+            let files = [
+                ("index.css", color_mix::output::stylesheet(options)?),
+                ("colors.css", color_mix::output::to_css(&served, options)),
+                ("index.html", color_mix::output::to_html(&served, options)),
+            ];
+            return serve::serve(addr, &files);
+        }
     }
-    .to_rgb())
+
+    Ok(())
 }
 
-fn random_color() -> RGB {
-    RGB {
-        r: Ratio::from_u8(rand::random::<u8>()),
-        g: Ratio::from_u8(rand::random::<u8>()),
-        b: Ratio::from_u8(rand::random::<u8>()),
-    }
+#[cfg(feature = "serve")]
+fn serving(options: &Options) -> bool {
+    options.serve.is_some()
 }
 
-fn create_iter(max_len: usize, rounds: usize) -> impl Iterator<Item = (usize, usize)> {
-    (2..=max_len).flat_map(move |input_len| iter::repeat(input_len).zip(0..rounds))
+#[cfg(not(feature = "serve"))]
+fn serving(_options: &Options) -> bool {
+    false
 }
 
-fn id(input_len: usize, round: usize) -> String {
-    format!("{}-{}", input_len, round)
+/// Creates the output directory with the stylesheet, and the writer of the
+/// page and the other requested outputs.
+fn page_writer<'a>(
+    metadata: &RunMetadata,
+    max_input_len: usize,
+    options: &'a Options,
+) -> io::Result<RunWriter<'a>> {
+    let out_dir = &options.out_dir;
+    fs::create_dir_all(out_dir)?;
+    fs::write(
+        out_dir.join("index.css"),
+        color_mix::output::stylesheet(options)?,
+    )?;
+
+    let mut sinks = vec![
+        Sink::Css(create(
+            &out_dir.join(metadata.file_name(&options.css_name)),
+        )?),
+        Sink::Html(create(
+            &out_dir.join(metadata.file_name(&options.html_name)),
+        )?),
+    ];
+    if let Some(path) = &options.scss {
+        sinks.push(Sink::Scss(create(path)?));
+    }
+    if let Some(path) = &options.chromaticity {
+        sinks.push(Sink::Chromaticity(create(path)?));
+    }
+    if options.palette_pages {
+        sinks.push(Sink::Pages(out_dir.join("palettes")));
+    }
+    RunWriter::new(sinks, metadata, max_input_len, options)
 }
 
-fn main() -> std::io::Result<()> {
-    let max_len = 5;
-    let rounds = 10;
-    let out_dir = Path::new("./out");
-    let res_dir = Path::new("./res");
-
-    let records: Vec<Record> = create_iter(max_len, rounds)
-        .map(|(input_len, round)| {
-            let input: Vec<_> = (0..input_len).map(|_| random_color()).collect();
-            let id = id(input_len, round);
-            let black = RGB {
-                r: Ratio::from_u8(0),
-                g: Ratio::from_u8(0),
-                b: Ratio::from_u8(0),
-            };
-            let rgb_avg = panic::catch_unwind(|| rgb_avg(&input))
-                .map_err(|_| ComputeError::Panic)
-                .and_then(|r| r)
-                .unwrap_or_else(|e| {
-                    eprintln!("WARN: {:?}: rgb_avg not computable for {:?}", e, &input);
-                    black
-                });
-            let less_mix = panic::catch_unwind(|| less_mix(&input))
-                .map_err(|_| ComputeError::Panic)
-                .and_then(|r| r)
-                .unwrap_or_else(|e| {
-                    eprintln!("WARN: {:?}: less_mix not computable for {:?}", e, &input);
-                    black
-                });
-            let hsl_geo = panic::catch_unwind(|| hsl_geo(&input))
-                .map_err(|_| ComputeError::Panic)
-                .and_then(|r| r)
-                .unwrap_or_else(|e| {
-                    eprintln!("WARN: {:?}: hsl_geo not computable for {:?}", e, &input);
-                    black
-                });
-            Record {
-                id,
-                input,
-                rgb_avg,
-                less_mix,
-                hsl_geo,
-            }
-        })
-        .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color_mix::options::PlaceholderStyle;
+    use color_mix::ComputeError;
+    use css_colors::{rgb, RGB};
 
-    let color_css: String = records.iter().map(|r| r.to_css()).collect();
+    /// A mixer that fails on every input, to exercise the failure handling.
+    struct Failing;
 
-    let html_content: String = records.iter().map(|r| r.to_html()).collect();
+    impl Mixer for Failing {
+        fn slug(&self) -> &str {
+            "failing"
+        }
 
-    let html = format!(
-        "<html>
- <head>
-<link rel='stylesheet' type='text/css' href='index.css'>
-<link rel='stylesheet' type='text/css' href='colors.css'>
-</head>
-<body>
-{}
-</body>
-</html>",
-        html_content
-    );
+        fn describe(&self) -> String {
+            "always fails".to_string()
+        }
 
-    fs::create_dir_all(out_dir)?;
+        fn mix_weighted(&self, _: &[RGB], _: &[f32]) -> Result<RGB, ComputeError> {
+            Err(ComputeError::NonFinite)
+        }
+    }
 
-    fs::copy(res_dir.join("index.css"), out_dir.join("index.css"))?;
+    /// The registry with `Failing` added, a record `passed` of two colors
+    /// mixed without `Failing` and a record `failed` of three colors mixed
+    /// with it.
+    fn records(options: &Options) -> (Vec<Box<dyn Mixer>>, Vec<Record>) {
+        let mut mixers = mixer::registry(options);
+        mixers.push(Box::new(Failing));
+        let input = vec![rgb(255, 0, 0), rgb(0, 0, 255), rgb(0, 255, 0)];
+        let records = vec![
+            generate::compute_record(
+                "passed".to_string(),
+                input[..2].to_vec(),
+                &mixer::registry(options),
+            ),
+            generate::compute_record("failed".to_string(), input, &mixers),
+        ];
+        (mixers, records)
+    }
 
-    let mut color_css_file = File::create(out_dir.join("colors.css"))?;
-    color_css_file.write_all(color_css.as_bytes())?;
-    drop(color_css_file);
+    /// Renders the `records` into a fresh `out_dir` named after `test`,
+    /// returning the result, the page and its `colors.css`.
+    fn render_page(test: &str, options: Options) -> (io::Result<()>, String, String) {
+        let out_dir =
+            std::env::temp_dir().join(format!("color-mix-{}-{}", test, std::process::id()));
+        let options = Options {
+            out_dir: out_dir.clone(),
+            ..options
+        };
+        let (mixers, records) = records(&options);
+        let metadata = RunMetadata::new(&mixers, &options);
+        let result = render(records.into_iter(), None, &metadata, 3, &mixers, &options);
+        let read = |name: &str| fs::read_to_string(out_dir.join(metadata.file_name(name))).unwrap();
+        let (page, css) = (read(&options.html_name), read(&options.css_name));
+        fs::remove_dir_all(&out_dir).unwrap();
+        (result, page, css)
+    }
 
-    let mut html_file = File::create(out_dir.join("index.html"))?;
-    html_file.write_all(html.as_bytes())?;
-    drop(html_file);
+    #[test]
+    fn failing_mixer_is_recorded_as_a_failure() {
+        let (_, records) = records(&Options::default());
+        assert!(records[0].failures.is_empty());
+        assert_eq!(records[1].failures.len(), 1);
+        assert_eq!(records[1].failures[0].mixer, "failing");
+        assert_eq!(
+            records[1].failures[0].error,
+            ComputeError::NonFinite.to_string()
+        );
+        assert_eq!(records[1].output("failing"), Some(rgb(0, 0, 0)));
+    }
 
-    Ok(())
+    #[test]
+    fn only_failures_renders_only_failed_records() {
+        let options = Options {
+            only_failures: true,
+            ..Options::default()
+        };
+        let (result, page, _) = render_page("only-failures", options);
+        assert!(result.is_ok());
+        assert!(page.contains("record-failed"));
+        assert!(!page.contains("record-passed"));
+    }
+
+    #[test]
+    fn strict_fails_the_run_after_rendering_every_record() {
+        let options = Options {
+            strict: true,
+            ..Options::default()
+        };
+        let (result, page, _) = render_page("strict", options);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "--strict: 1 of 2 records failed"
+        );
+        assert!(page.contains("record-failed"));
+        assert!(page.contains("record-passed"));
+    }
+
+    #[test]
+    fn placeholder_style_applies_with_a_custom_stylesheet() {
+        let stylesheet =
+            std::env::temp_dir().join(format!("color-mix-stylesheet-{}.css", std::process::id()));
+        fs::write(&stylesheet, "body {}\n").unwrap();
+        let options = Options {
+            stylesheet: Some(stylesheet.clone()),
+            align_grid: true,
+            placeholder_style: PlaceholderStyle::Outline,
+            ..Options::default()
+        };
+        let (result, page, css) = render_page("placeholder-style", options);
+        fs::remove_file(&stylesheet).unwrap();
+        assert!(result.is_ok());
+        assert!(css.contains(".placeholder-outline {"));
+        assert!(page.contains("placeholder placeholder-outline"));
+    }
 }
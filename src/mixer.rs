@@ -0,0 +1,948 @@
+//! The strategies as [`Mixer`] values, and the registry of the mixers a run
+//! compares.
+//!
+//! Records, CSS, HTML and every other output iterate over the registry, so a
+//! new strategy only needs a `Mixer` implementation and an entry in
+//! [`registry`].
+
+use crate::accumulator::{Accumulator, Buffered, Running};
+use crate::alpha;
+use crate::blend::{Achromatic, HuePath, HueVote, MixSpace};
+use crate::blend_mode::BlendMode;
+use crate::cam16::ViewingConditions;
+use crate::chroma;
+use crate::css_mix::CssMixSpace;
+use crate::luminance;
+use crate::space;
+use crate::temperature;
+use crate::{
+    additive_light_weighted, blend_modes_weighted, cam16_avg_weighted, cmyk_avg_weighted,
+    composite_over_weighted, css_color_mix_weighted, geometric_mean_weighted, hsl_geo_voted,
+    hsv_geo_weighted, kmeans_dominant_weighted, kubelka_munk_weighted, lab_avg_weighted,
+    lch_avg_weighted, less_mix_weighted, linear_avg_weighted, lms_avg_weighted,
+    luminance_avg_weighted, mean_of_strategies, median_weighted, oklab_avg_weighted,
+    oklch_avg_weighted, progressive_mix_weighted, rgb_avg_weighted, run_strategy,
+    spectral_mix_weighted, subtractive_filter_weighted, trimmed_mean_weighted, xyz_avg_weighted,
+    ycbcr_avg_weighted,
+};
+use crate::{ComputeError, Options};
+use css_colors::RGB;
+
+/// A strategy mixing a slice of colors into one.
+pub trait Mixer: Send + Sync {
+    /// Identifier used in CSS classes and on the command line.
+    fn slug(&self) -> &str;
+
+    /// The space the strategy works in and how it combines the inputs there.
+    fn describe(&self) -> String;
+
+    /// Mixes `input`, weighing every color by the weight at the same index
+    /// of `weights`.
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError>;
+
+    /// An [`Accumulator`] mixing colors pushed one at a time like this
+    /// mixer. By default it collects them and mixes them in `finish`, the
+    /// averaging mixers keep running sums instead.
+    fn accumulator(&self) -> Box<dyn Accumulator + '_> {
+        Box::new(Buffered::new(self))
+    }
+
+    /// Mixes `input` with equal weights.
+    fn mix(&self, input: &[RGB]) -> Result<RGB, ComputeError> {
+        self.mix_weighted(input, &vec![1.0; input.len()])
+    }
+
+    /// Mixes translucent `input` with an alpha per color, premultiplied as
+    /// described in [`alpha`]. Returns the color and alpha of the mix, a
+    /// mix of only transparent inputs is transparent.
+    fn mix_rgba(
+        &self,
+        input: &[RGB],
+        weights: &[f32],
+        alphas: &[f32],
+    ) -> Result<(RGB, f32), ComputeError> {
+        alpha::check_alphas(input.len(), alphas)?;
+        let color = match alpha::premultiplied_weights(weights, alphas) {
+            Some(premultiplied) => self.mix_weighted(input, &premultiplied)?,
+            None => self.mix_weighted(input, weights)?,
+        };
+        Ok((color, alpha::mixed_alpha(weights, alphas)))
+    }
+}
+
+/// See [`rgb_avg`](crate::rgb_avg).
+pub struct RgbAvg;
+
+impl Mixer for RgbAvg {
+    fn slug(&self) -> &'static str {
+        "rgb-avg"
+    }
+
+    fn describe(&self) -> String {
+        "averaged in gamma encoded sRGB".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        rgb_avg_weighted(input, weights)
+    }
+
+    fn accumulator(&self) -> Box<dyn Accumulator + '_> {
+        Box::new(Running::new(space::SrgbSpace))
+    }
+}
+
+/// See [`linear_avg`](crate::linear_avg).
+pub struct LinearAvg;
+
+impl Mixer for LinearAvg {
+    fn slug(&self) -> &'static str {
+        "linear-avg"
+    }
+
+    fn describe(&self) -> String {
+        "averaged in linear-light sRGB".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        linear_avg_weighted(input, weights)
+    }
+
+    fn accumulator(&self) -> Box<dyn Accumulator + '_> {
+        Box::new(Running::new(space::LinearSpace))
+    }
+}
+
+/// See [`luminance_avg`](crate::luminance_avg).
+pub struct LuminanceWeighted;
+
+impl Mixer for LuminanceWeighted {
+    fn slug(&self) -> &'static str {
+        "luminance-weighted"
+    }
+
+    fn describe(&self) -> String {
+        "averaged in linear-light sRGB weighted by relative luminance".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        luminance_avg_weighted(input, weights)
+    }
+}
+
+/// See [`xyz_avg`](crate::xyz_avg).
+pub struct XyzAvg;
+
+impl Mixer for XyzAvg {
+    fn slug(&self) -> &'static str {
+        "xyz-avg"
+    }
+
+    fn describe(&self) -> String {
+        "averaged in CIE XYZ".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        xyz_avg_weighted(input, weights)
+    }
+
+    fn accumulator(&self) -> Box<dyn Accumulator + '_> {
+        Box::new(Running::new(space::XyzSpace))
+    }
+}
+
+/// See [`ycbcr_avg`](crate::ycbcr_avg).
+pub struct YcbcrAvg;
+
+impl Mixer for YcbcrAvg {
+    fn slug(&self) -> &'static str {
+        "ycbcr-avg"
+    }
+
+    fn describe(&self) -> String {
+        "averaged luma and chroma in BT.709 Y'CbCr".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        ycbcr_avg_weighted(input, weights)
+    }
+
+    fn accumulator(&self) -> Box<dyn Accumulator + '_> {
+        Box::new(Running::new(space::YcbcrSpace))
+    }
+}
+
+/// See [`lms_avg`](crate::lms_avg).
+pub struct LmsAvg;
+
+impl Mixer for LmsAvg {
+    fn slug(&self) -> &'static str {
+        "lms-avg"
+    }
+
+    fn describe(&self) -> String {
+        "averaged the LMS cone responses".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        lms_avg_weighted(input, weights)
+    }
+
+    fn accumulator(&self) -> Box<dyn Accumulator + '_> {
+        Box::new(Running::new(space::LmsSpace))
+    }
+}
+
+/// See [`lab_avg`](crate::lab_avg).
+pub struct LabAvg;
+
+impl Mixer for LabAvg {
+    fn slug(&self) -> &'static str {
+        "lab-avg"
+    }
+
+    fn describe(&self) -> String {
+        "averaged in CIELAB".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        lab_avg_weighted(input, weights)
+    }
+
+    fn accumulator(&self) -> Box<dyn Accumulator + '_> {
+        Box::new(Running::new(space::LabSpace))
+    }
+}
+
+/// See [`lch_avg`](crate::lch_avg).
+pub struct LchAvg;
+
+impl Mixer for LchAvg {
+    fn slug(&self) -> &'static str {
+        "lch-avg"
+    }
+
+    fn describe(&self) -> String {
+        "averaged in CIE LCh with a circular hue mean".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        lch_avg_weighted(input, weights)
+    }
+}
+
+/// See [`oklab_avg`](crate::oklab_avg).
+pub struct OklabAvg;
+
+impl Mixer for OklabAvg {
+    fn slug(&self) -> &'static str {
+        "oklab-avg"
+    }
+
+    fn describe(&self) -> String {
+        "averaged in OKLab".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        oklab_avg_weighted(input, weights)
+    }
+
+    fn accumulator(&self) -> Box<dyn Accumulator + '_> {
+        Box::new(Running::new(space::OklabSpace))
+    }
+}
+
+/// See [`oklch_avg`](crate::oklch_avg).
+pub struct OklchAvg {
+    pub hue_path: HuePath,
+}
+
+impl Mixer for OklchAvg {
+    fn slug(&self) -> &'static str {
+        "oklch-avg"
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "interpolated in OKLCH along the {} hue arc",
+            self.hue_path.name()
+        )
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        oklch_avg_weighted(input, weights, self.hue_path)
+    }
+}
+
+/// See [`cam16_avg`](crate::cam16_avg).
+pub struct Cam16Avg {
+    pub conditions: ViewingConditions,
+}
+
+impl Mixer for Cam16Avg {
+    fn slug(&self) -> &'static str {
+        "cam16-avg"
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "averaged in CAM16-UCS at L_A {} cd/m², Y_b {}, {:?} surround",
+            self.conditions.adapting_luminance,
+            self.conditions.background_luminance,
+            self.conditions.surround
+        )
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        cam16_avg_weighted(input, weights, &self.conditions)
+    }
+}
+
+/// See [`geometric_mean`](crate::geometric_mean).
+pub struct GeometricMean;
+
+impl Mixer for GeometricMean {
+    fn slug(&self) -> &'static str {
+        "geometric-mean"
+    }
+
+    fn describe(&self) -> String {
+        "took the geometric mean of every linear-light sRGB channel".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        geometric_mean_weighted(input, weights)
+    }
+}
+
+/// See [`kubelka_munk`](crate::kubelka_munk).
+pub struct KubelkaMunk;
+
+impl Mixer for KubelkaMunk {
+    fn slug(&self) -> &'static str {
+        "kubelka-munk"
+    }
+
+    fn describe(&self) -> String {
+        "mixed as paints by averaging the Kubelka-Munk K/S of pigment spectra".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        kubelka_munk_weighted(input, weights)
+    }
+}
+
+/// See [`spectral_mix`](crate::spectral_mix).
+pub struct SpectralMix;
+
+impl Mixer for SpectralMix {
+    fn slug(&self) -> &'static str {
+        "spectral"
+    }
+
+    fn describe(&self) -> String {
+        "mixed as paints per band of upsampled reflectance spectra".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        spectral_mix_weighted(input, weights)
+    }
+}
+
+/// See [`cmyk_avg`](crate::cmyk_avg).
+pub struct CmykAvg;
+
+impl Mixer for CmykAvg {
+    fn slug(&self) -> &'static str {
+        "cmyk-avg"
+    }
+
+    fn describe(&self) -> String {
+        "averaged the inks of naive CMYK".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        cmyk_avg_weighted(input, weights)
+    }
+}
+
+/// See [`additive_light`](crate::additive_light).
+pub struct AdditiveLight {
+    pub normalize: bool,
+}
+
+impl Mixer for AdditiveLight {
+    fn slug(&self) -> &'static str {
+        "additive-light"
+    }
+
+    fn describe(&self) -> String {
+        if self.normalize {
+            "summed the linear-light channels, scaled down into the gamut".to_string()
+        } else {
+            "summed the linear-light channels, clipped to the gamut".to_string()
+        }
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        additive_light_weighted(input, weights, self.normalize)
+    }
+}
+
+/// See [`subtractive_filter`](crate::subtractive_filter).
+pub struct SubtractiveFilter;
+
+impl Mixer for SubtractiveFilter {
+    fn slug(&self) -> &'static str {
+        "subtractive-filter"
+    }
+
+    fn describe(&self) -> String {
+        "multiplied the linear-light channels like stacked filters".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        subtractive_filter_weighted(input, weights)
+    }
+}
+
+/// See [`composite_over`](crate::composite_over).
+pub struct CompositeOver {
+    pub opacities: Vec<f32>,
+}
+
+impl Mixer for CompositeOver {
+    fn slug(&self) -> &'static str {
+        "over"
+    }
+
+    fn describe(&self) -> String {
+        let opacities: Vec<String> = self.opacities.iter().map(|o| o.to_string()).collect();
+        format!(
+            "composited every input over the previous at opacity {}",
+            opacities.join(", ")
+        )
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        composite_over_weighted(input, weights, &self.opacities)
+    }
+}
+
+/// See [`blend_modes`](crate::blend_modes).
+pub struct BlendModes {
+    pub mode: BlendMode,
+}
+
+impl Mixer for BlendModes {
+    fn slug(&self) -> &'static str {
+        "blend-mode"
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "blended every input onto the previous with {}",
+            self.mode.name()
+        )
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        blend_modes_weighted(input, weights, self.mode)
+    }
+}
+
+/// See [`css_color_mix`](crate::css_color_mix).
+pub struct CssColorMix {
+    pub space: CssMixSpace,
+    pub hue_path: HuePath,
+}
+
+impl Mixer for CssColorMix {
+    fn slug(&self) -> &'static str {
+        "css-color-mix"
+    }
+
+    fn describe(&self) -> String {
+        if self.space.is_polar() {
+            format!(
+                "mixed like CSS color-mix() in {} along the {} hue arc",
+                self.space.name(),
+                self.hue_path.name()
+            )
+        } else {
+            format!("mixed like CSS color-mix() in {}", self.space.name())
+        }
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        css_color_mix_weighted(input, weights, self.space, self.hue_path)
+    }
+}
+
+/// See [`median`](crate::median).
+pub struct Median;
+
+impl Mixer for Median {
+    fn slug(&self) -> &'static str {
+        "median"
+    }
+
+    fn describe(&self) -> String {
+        "took the median of every sRGB channel".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        median_weighted(input, weights)
+    }
+}
+
+/// See [`kmeans_dominant`](crate::kmeans_dominant).
+pub struct KmeansDominant {
+    pub k: usize,
+}
+
+impl Mixer for KmeansDominant {
+    fn slug(&self) -> &'static str {
+        "kmeans"
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "took the centroid of the heaviest of {} k-means clusters in OKLab",
+            self.k
+        )
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        kmeans_dominant_weighted(input, weights, self.k)
+    }
+}
+
+/// See [`trimmed_mean`](crate::trimmed_mean).
+pub struct TrimmedMean {
+    pub trim: f32,
+}
+
+impl Mixer for TrimmedMean {
+    fn slug(&self) -> &'static str {
+        "trimmed-mean"
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "averaged every sRGB channel without the lowest and highest {}%",
+            self.trim * 100.0
+        )
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        trimmed_mean_weighted(input, weights, self.trim)
+    }
+}
+
+/// See [`less_mix`](crate::less_mix).
+pub struct LessMix {
+    pub space: MixSpace,
+}
+
+impl Mixer for LessMix {
+    fn slug(&self) -> &'static str {
+        "less-mix"
+    }
+
+    fn describe(&self) -> String {
+        format!("folded pairwise blends in {:?}", self.space)
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        less_mix_weighted(input, weights, self.space)
+    }
+}
+
+/// See [`progressive_mix`](crate::progressive_mix).
+pub struct ProgressiveMix {
+    pub space: MixSpace,
+}
+
+impl Mixer for ProgressiveMix {
+    fn slug(&self) -> &'static str {
+        "progressive-mix"
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "folded pairwise blends in {:?} with equal shares",
+            self.space
+        )
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        progressive_mix_weighted(input, weights, self.space)
+    }
+}
+
+/// See [`hsl_geo`](crate::hsl_geo). With `normalize_luminance`, the result
+/// is given the average relative luminance of the inputs. `hue_vote` scales
+/// the hue vectors and `achromatic` handles grays, see [`hsl_geo_voted`].
+pub struct HslGeo {
+    pub normalize_luminance: bool,
+    pub hue_vote: HueVote,
+    pub achromatic: Achromatic,
+}
+
+impl Mixer for HslGeo {
+    fn slug(&self) -> &'static str {
+        "hsl-geo"
+    }
+
+    fn describe(&self) -> String {
+        match self.hue_vote {
+            HueVote::Equal => "averaged in HSL with a circular hue mean".to_string(),
+            vote => format!(
+                "averaged in HSL with a circular hue mean weighted by {}",
+                vote.name()
+            ),
+        }
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        if self.normalize_luminance {
+            luminance::with_normalized_luminance(input, weights, |input| {
+                hsl_geo_voted(input, weights, self.hue_vote, self.achromatic)
+            })
+        } else {
+            hsl_geo_voted(input, weights, self.hue_vote, self.achromatic)
+        }
+    }
+}
+
+/// See [`hsv_geo`](crate::hsv_geo).
+pub struct HsvGeo;
+
+impl Mixer for HsvGeo {
+    fn slug(&self) -> &'static str {
+        "hsv-geo"
+    }
+
+    fn describe(&self) -> String {
+        "averaged in HSV with a circular hue mean".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        hsv_geo_weighted(input, weights)
+    }
+}
+
+/// See [`mean_of_strategies`]. The consensus of `rgb-avg`, `less-mix` and
+/// `hsl-geo`, which get the weights of the inputs while their results are
+/// averaged equally. A member that fails is logged and left out of the
+/// consensus.
+pub struct MeanOfStrategies(pub Vec<Box<dyn Mixer>>);
+
+impl MeanOfStrategies {
+    /// The consensus of `rgb-avg`, `less-mix` and `hsl-geo` with the mix
+    /// space and HSL settings of `options`.
+    pub fn new(options: &Options) -> MeanOfStrategies {
+        MeanOfStrategies(vec![
+            Box::new(RgbAvg),
+            Box::new(LessMix {
+                space: options.mix_space,
+            }),
+            Box::new(HslGeo {
+                normalize_luminance: options.normalize_luminance,
+                hue_vote: options.hue_vote,
+                achromatic: options.achromatic,
+            }),
+        ])
+    }
+}
+
+impl Mixer for MeanOfStrategies {
+    fn slug(&self) -> &'static str {
+        "mean-of-strategies"
+    }
+
+    fn describe(&self) -> String {
+        "averaged the results of rgb-avg, less-mix and hsl-geo in sRGB".to_string()
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        let results: Vec<RGB> = self
+            .0
+            .iter()
+            .filter_map(|mixer| {
+                match run_strategy(mixer.slug(), || mixer.mix_weighted(input, weights)) {
+                    Ok(color) => Some(color),
+                    Err((slug, e)) => {
+                        log::warn!("mean-of-strategies: left out {}, it failed: {}", slug, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        mean_of_strategies(&results)
+    }
+}
+
+/// Runs the inner mixer on pairs only, like the rounds of a tournament:
+/// neighbouring inputs are mixed by their weights, the mixes carry the sum
+/// of their weights into the next round, and an odd input out moves up on
+/// its own, until one color is left. Compared with mixing all inputs at
+/// once this shows how much a strategy depends on the order it combines
+/// colors in.
+pub struct Tournament(pub Box<dyn Mixer>);
+
+impl Mixer for Tournament {
+    fn slug(&self) -> &str {
+        self.0.slug()
+    }
+
+    fn describe(&self) -> String {
+        format!("{}, pairwise in a balanced tree", self.0.describe())
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        if input.is_empty() {
+            return Err(ComputeError::EmptyInput);
+        }
+        if weights.len() != input.len() {
+            return Err(ComputeError::InvalidWeights);
+        }
+        let mut round: Vec<(RGB, f32)> =
+            input.iter().copied().zip(weights.iter().copied()).collect();
+        while round.len() > 1 {
+            round = round
+                .chunks(2)
+                .map(|pair| match *pair {
+                    [(a, wa), (b, wb)] if wa + wb > 0.0 => {
+                        Ok((self.0.mix_weighted(&[a, b], &[wa, wb])?, wa + wb))
+                    }
+                    [first, ..] => Ok(first),
+                    [] => unreachable!("chunks are never empty"),
+                })
+                .collect::<Result<_, ComputeError>>()?;
+        }
+        Ok(round[0].0)
+    }
+}
+
+/// Runs the inner mixer and gives its result the mean relative luminance of
+/// the inputs, keeping its hue and saturation. Averaging in gamma encoded
+/// spaces darkens mixes, this shows the hue a strategy picks at the
+/// brightness the inputs have.
+pub struct PreserveBrightness(pub Box<dyn Mixer>);
+
+impl Mixer for PreserveBrightness {
+    fn slug(&self) -> &str {
+        self.0.slug()
+    }
+
+    fn describe(&self) -> String {
+        format!("{}, at the mean input luminance", self.0.describe())
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        let mixed = self.0.mix_weighted(input, weights)?;
+        Ok(match luminance::mean_luminance(input, weights) {
+            Some(target) => luminance::with_luminance(mixed, target),
+            None => mixed,
+        })
+    }
+}
+
+/// Runs the inner mixer and white balances its result to the mean
+/// correlated color temperature of the inputs, like a photographer
+/// matching the white balance of a mix to the light of its parts.
+pub struct MatchTemperature(pub Box<dyn Mixer>);
+
+impl Mixer for MatchTemperature {
+    fn slug(&self) -> &str {
+        self.0.slug()
+    }
+
+    fn describe(&self) -> String {
+        format!("{}, at the mean input temperature", self.0.describe())
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        let mixed = self.0.mix_weighted(input, weights)?;
+        Ok(match temperature::mean_cct(input, weights) {
+            Some(target) => temperature::with_cct(mixed, target),
+            None => mixed,
+        })
+    }
+}
+
+/// Runs the inner mixer and gives its result the mean OKLCH chroma of the
+/// inputs, keeping its lightness and hue, as far as the sRGB gamut allows.
+/// Averaging opposing hues cancels their chroma, this shows the hue a
+/// strategy picks at the colorfulness the inputs have.
+pub struct PreserveChroma(pub Box<dyn Mixer>);
+
+impl Mixer for PreserveChroma {
+    fn slug(&self) -> &str {
+        self.0.slug()
+    }
+
+    fn describe(&self) -> String {
+        format!("{}, at the mean input chroma", self.0.describe())
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        let mixed = self.0.mix_weighted(input, weights)?;
+        Ok(match chroma::mean_chroma(input, weights) {
+            Some(target) => chroma::with_chroma(mixed, target),
+            None => mixed,
+        })
+    }
+}
+
+/// The mixers of a run, configured from `options`, in output order. Only
+/// the mixers listed in `Options::mixers` are included if it is set, the
+/// `Options::mixer_commands` and the plugins of `Options::plugin_dir`
+/// always come last. Every mixer is wrapped in a [`Tournament`] with
+/// `Options::tournament`, around that in a [`PreserveBrightness`] with
+/// `Options::preserve_brightness`, a [`MatchTemperature`] with
+/// `Options::match_temperature` and a [`PreserveChroma`] with
+/// `Options::preserve_chroma`.
+pub fn registry(options: &Options) -> Vec<Box<dyn Mixer>> {
+    let mut mixers: Vec<Box<dyn Mixer>> = vec![
+        Box::new(RgbAvg),
+        Box::new(YcbcrAvg),
+        Box::new(LinearAvg),
+        Box::new(LuminanceWeighted),
+        Box::new(XyzAvg),
+        Box::new(LmsAvg),
+        Box::new(LabAvg),
+        Box::new(LchAvg),
+        Box::new(OklabAvg),
+        Box::new(OklchAvg {
+            hue_path: options.hue_path,
+        }),
+        Box::new(Cam16Avg {
+            conditions: options.cam16,
+        }),
+        Box::new(GeometricMean),
+        Box::new(KubelkaMunk),
+        Box::new(SpectralMix),
+        Box::new(CmykAvg),
+        Box::new(AdditiveLight {
+            normalize: options.normalize_light,
+        }),
+        Box::new(SubtractiveFilter),
+        Box::new(Median),
+        Box::new(TrimmedMean {
+            trim: options.trim_fraction,
+        }),
+        Box::new(KmeansDominant {
+            k: options.kmeans_k,
+        }),
+        Box::new(LessMix {
+            space: options.mix_space,
+        }),
+        Box::new(ProgressiveMix {
+            space: options.mix_space,
+        }),
+        Box::new(HslGeo {
+            normalize_luminance: options.normalize_luminance,
+            hue_vote: options.hue_vote,
+            achromatic: options.achromatic,
+        }),
+        Box::new(HsvGeo),
+        Box::new(CompositeOver {
+            opacities: options.layer_opacities.clone(),
+        }),
+        Box::new(BlendModes {
+            mode: options.blend_mode,
+        }),
+        Box::new(CssColorMix {
+            space: options.css_mix_space,
+            hue_path: options.hue_path,
+        }),
+    ];
+    mixers.push(Box::new(MeanOfStrategies::new(options)));
+    if let Some(enabled) = &options.mixers {
+        mixers.retain(|m| enabled.iter().any(|slug| slug == m.slug()));
+    }
+    for command in &options.mixer_commands {
+        if mixers.iter().any(|m| m.slug() == command.slug()) {
+            log::warn!(
+                "skipping mixer command {}, the name is taken",
+                command.slug()
+            );
+        } else {
+            mixers.push(Box::new(command.clone()));
+        }
+    }
+    #[cfg(feature = "wasm-plugins")]
+    {
+        if let Some(dir) = &options.plugin_dir {
+            for plugin in crate::wasm_plugin::load_dir(dir) {
+                if mixers.iter().any(|m| m.slug() == plugin.slug()) {
+                    log::warn!("skipping plugin {}, the name is taken", plugin.slug());
+                } else {
+                    mixers.push(Box::new(plugin));
+                }
+            }
+        }
+    }
+    if options.tournament {
+        mixers = mixers
+            .into_iter()
+            .map(|m| Box::new(Tournament(m)) as Box<dyn Mixer>)
+            .collect();
+    }
+    if options.preserve_brightness {
+        mixers = mixers
+            .into_iter()
+            .map(|m| Box::new(PreserveBrightness(m)) as Box<dyn Mixer>)
+            .collect();
+    }
+    if options.match_temperature {
+        mixers = mixers
+            .into_iter()
+            .map(|m| Box::new(MatchTemperature(m)) as Box<dyn Mixer>)
+            .collect();
+    }
+    if options.preserve_chroma {
+        mixers = mixers
+            .into_iter()
+            .map(|m| Box::new(PreserveChroma(m)) as Box<dyn Mixer>)
+            .collect();
+    }
+    mixers
+}
+
+/// The description of the mixer with `slug`, if it is registered.
+pub fn describe(mixers: &[Box<dyn Mixer>], slug: &str) -> Option<String> {
+    mixers
+        .iter()
+        .find(|m| m.slug() == slug)
+        .map(|m| m.describe())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use css_colors::rgb;
+
+    #[test]
+    fn mean_of_strategies_of_identical_inputs_is_that_input() {
+        let color = rgb(90, 140, 200);
+        let mean = MeanOfStrategies::new(&Options::default());
+        let result = mean.mix(&[color, color, color]).unwrap();
+        assert_eq!(result, color);
+    }
+
+    #[test]
+    fn normalized_hsl_geo_keeps_a_shared_hue() {
+        let mixer = HslGeo {
+            normalize_luminance: true,
+            hue_vote: HueVote::Equal,
+            achromatic: Achromatic::Vote,
+        };
+        let result = mixer.mix(&[rgb(120, 20, 20), rgb(255, 150, 150)]).unwrap();
+        // Reds with equal green and blue have a hue of exactly 0°.
+        assert_eq!(result.g, result.b);
+        assert!(result.r > result.g);
+    }
+}
@@ -0,0 +1,555 @@
+//! Everything that configures a run.
+
+use crate::blend::{Achromatic, HuePath, HueVote, MixSpace};
+use crate::blend_mode::BlendMode;
+use crate::cam16::ViewingConditions;
+use crate::command_mixer::CommandMixer;
+use crate::css_color;
+use crate::css_mix::CssMixSpace;
+use crate::cvd::Cvd;
+use crate::easing::Easing;
+use crate::generate::{Preset, Sampling};
+use crate::named;
+use crate::quantize::Palette;
+use css_colors::{Ratio, RGB};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// The color each output swatch is compared against by `--annotate-delta`.
+#[derive(Debug, Clone, Copy)]
+pub enum DeltaBaseline {
+    FirstInput,
+    RgbAvg,
+}
+
+impl FromStr for DeltaBaseline {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first" => Ok(DeltaBaseline::FirstInput),
+            "rgb-avg" => Ok(DeltaBaseline::RgbAvg),
+            _ => Err(format!(
+                "unknown baseline `{}`, expected first or rgb-avg",
+                s
+            )),
+        }
+    }
+}
+
+/// Syntax used for colors in the generated CSS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CssColorFormat {
+    /// `rgb(255, 128, 0)`
+    Legacy,
+    /// The CSS Color 4 space separated `rgb(255 128 0)`
+    ModernRgb,
+}
+
+impl FromStr for CssColorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "legacy" => Ok(CssColorFormat::Legacy),
+            "modern-rgb" => Ok(CssColorFormat::ModernRgb),
+            _ => Err(format!(
+                "unknown CSS color format `{}`, expected legacy or modern-rgb",
+                s
+            )),
+        }
+    }
+}
+
+/// How the empty input slots of `--align-grid` are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceholderStyle {
+    Hidden,
+    Checkerboard,
+    Outline,
+}
+
+impl FromStr for PlaceholderStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hidden" => Ok(PlaceholderStyle::Hidden),
+            "checkerboard" => Ok(PlaceholderStyle::Checkerboard),
+            "outline" => Ok(PlaceholderStyle::Outline),
+            _ => Err(format!(
+                "unknown placeholder style `{}`, expected hidden, checkerboard or outline",
+                s
+            )),
+        }
+    }
+}
+
+impl PlaceholderStyle {
+    /// The CSS class placeholders of this style get.
+    pub fn class(self) -> &'static str {
+        match self {
+            PlaceholderStyle::Hidden => "placeholder-hidden",
+            PlaceholderStyle::Checkerboard => "placeholder-checkerboard",
+            PlaceholderStyle::Outline => "placeholder-outline",
+        }
+    }
+
+    /// The CSS declarations of `class`.
+    pub fn declarations(self) -> &'static str {
+        match self {
+            PlaceholderStyle::Hidden => "    visibility: hidden;\n",
+            PlaceholderStyle::Checkerboard => {
+                "    background-image: repeating-conic-gradient(#ccc 0% 25%, white 0% 50%);
+    background-size: 1em 1em;\n"
+            }
+            PlaceholderStyle::Outline => {
+                "    box-sizing: border-box;
+    border: 2px dashed #999;\n"
+            }
+        }
+    }
+}
+
+/// Everything that configures a run.
+#[derive(Debug)]
+pub struct Options {
+    /// Smallest number of inputs per record.
+    pub min_len: usize,
+    pub max_len: usize,
+    pub rounds: usize,
+    /// Seed of the random number generator, so a run can be reproduced.
+    pub seed: Option<u64>,
+    /// Directory the page is written to.
+    pub out_dir: PathBuf,
+    /// File name of the page in `out_dir`, a template expanded by
+    /// `RunMetadata::file_name`.
+    pub html_name: String,
+    /// File name of the swatch colors in `out_dir`, a template like
+    /// `html_name`.
+    pub css_name: String,
+    /// Slugs of the mixers to run, all registered mixers if `None`.
+    pub mixers: Option<Vec<String>>,
+    /// CSS lengths overriding the stylesheet's swatch sizes.
+    pub input_swatch_size: Option<String>,
+    pub output_swatch_size: Option<String>,
+    pub annotate_delta: Option<DeltaBaseline>,
+    /// File of palettes to mix instead of random colors, see
+    /// [`input`](crate::input).
+    pub input: Option<PathBuf>,
+    /// Input file of palettes to mix like `input`, written from the drawn
+    /// palettes first if it does not exist, so later runs mix the same.
+    pub fixtures: Option<PathBuf>,
+    /// URL of palettes to mix like `input` with the `from-url` feature, see
+    /// `input::load_url`.
+    pub from_url: Option<String>,
+    /// Directory of input files to mix together like `input`, see
+    /// [`input::load_dir`](crate::input::load_dir).
+    pub input_dir: Option<PathBuf>,
+    /// Also write a page of its own for every record, next to the report.
+    pub palette_pages: bool,
+    /// CIEDE2000 distance below which an input collapses into an earlier
+    /// one before mixing, see [`dedup`](crate::dedup).
+    pub dedup: Option<f32>,
+    /// Skip colors and palettes that cannot be parsed with a warning instead
+    /// of failing.
+    pub lenient: bool,
+    pub image: Option<PathBuf>,
+    pub sample_grid: Option<(u32, u32)>,
+    /// Step the hues of random palettes by the golden angle, moving each by
+    /// up to this jitter in degrees, see
+    /// [`draw_palette`](crate::generate::draw_palette).
+    pub golden_hues: Option<f32>,
+    /// Space random colors are drawn uniformly from.
+    pub sampling: Sampling,
+    /// Region of HSL random colors are drawn from, overridden by the ranges
+    /// below.
+    pub preset: Option<Preset>,
+    /// Hues in degrees random colors are drawn from, wrapping around 360°
+    /// if the first is larger, like `(330.0, 30.0)` for reds.
+    pub hue_range: Option<(f32, f32)>,
+    /// HSL saturations and lightnesses in percent random colors are drawn
+    /// from, see [`draw_color`](crate::generate::draw_color).
+    pub sat_range: Option<(f32, f32)>,
+    pub light_range: Option<(f32, f32)>,
+    pub palette_names: bool,
+    pub mix_space: MixSpace,
+    /// The hue arc `oklch-avg` and `css-color-mix` in polar spaces
+    /// interpolate along.
+    pub hue_path: HuePath,
+    /// How the colors `cam16-avg` mixes are viewed.
+    pub cam16: ViewingConditions,
+    /// Fraction of the inputs `trimmed-mean` discards per end, in
+    /// `0.0..0.5`.
+    pub trim_fraction: f32,
+    /// Number of clusters `kmeans` splits the inputs into.
+    pub kmeans_k: usize,
+    /// Scale sums of `additive-light` beyond white down instead of clipping
+    /// them.
+    pub normalize_light: bool,
+    /// Opacity of every layer `over` composites onto the first input, the
+    /// last one repeats for further layers. Never empty.
+    pub layer_opacities: Vec<f32>,
+    /// The mode `blend-mode` blends the inputs with.
+    pub blend_mode: BlendMode,
+    /// The interpolation space of `css-color-mix`.
+    pub css_mix_space: CssMixSpace,
+    pub best_representative: bool,
+    pub chromaticity: Option<PathBuf>,
+    pub precision: usize,
+    pub scss: Option<PathBuf>,
+    pub only_failures: bool,
+    /// Fail the run if any mixer fails instead of falling back to black.
+    pub strict: bool,
+    /// Palette every output is also shown snapped to.
+    pub quantize: Option<Palette>,
+    /// Elevations in dp to render `on_surface` variants of every output for.
+    pub elevations: Vec<u8>,
+    pub surface: RGB,
+    pub css_color_format: CssColorFormat,
+    pub cvd: Option<Cvd>,
+    /// Records whose outputs are less distinguishable than this ΔE2000 under
+    /// `cvd` are flagged.
+    pub cvd_threshold: f32,
+    /// Pad every record's inputs to the longest record so outputs line up.
+    pub align_grid: bool,
+    pub placeholder_style: PlaceholderStyle,
+    /// Mix hues of inputs moved to a common luminance.
+    pub normalize_luminance: bool,
+    /// Render every mixer of two-color records as a strip of this many mixes
+    /// from the first color to the second.
+    pub strip_steps: Option<usize>,
+    /// How the share of the second color grows along the strips.
+    pub easing: Easing,
+    /// Mix the inputs pairwise in a balanced tree with every mixer.
+    pub tournament: bool,
+    /// Give the result of every mixer the mean luminance of its inputs.
+    pub preserve_brightness: bool,
+    /// White balance the result of every mixer to the mean color
+    /// temperature of its inputs.
+    pub match_temperature: bool,
+    /// Give the result of every mixer the mean OKLCH chroma of its inputs.
+    pub preserve_chroma: bool,
+    /// How much the hue of every input counts in `hsl-geo`.
+    pub hue_vote: HueVote,
+    /// What `hsl-geo` does with gray inputs.
+    pub achromatic: Achromatic,
+    /// Pair of strategy slugs whose difference is explained for every record.
+    pub explain_diff: Option<(String, String)>,
+    pub gamut_coverage: bool,
+    /// Where to save the run as JSON.
+    pub save_json: Option<PathBuf>,
+    /// Stylesheet written as `index.css` instead of the built-in one.
+    pub stylesheet: Option<PathBuf>,
+    /// External programs to run as mixers after the built-in ones.
+    pub mixer_commands: Vec<CommandMixer>,
+    /// Directory of WebAssembly mixers to run after the built-in ones.
+    #[cfg(feature = "wasm-plugins")]
+    pub plugin_dir: Option<PathBuf>,
+    #[cfg(feature = "serve")]
+    pub serve: Option<String>,
+    #[cfg(feature = "gif-output")]
+    pub gif: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            min_len: 2,
+            max_len: 5,
+            rounds: 10,
+            seed: None,
+            out_dir: PathBuf::from("out"),
+            html_name: "index.html".to_string(),
+            css_name: "colors.css".to_string(),
+            mixers: None,
+            input_swatch_size: None,
+            output_swatch_size: None,
+            annotate_delta: None,
+            input: None,
+            fixtures: None,
+            from_url: None,
+            input_dir: None,
+            palette_pages: false,
+            dedup: None,
+            lenient: false,
+            image: None,
+            sample_grid: None,
+            golden_hues: None,
+            sampling: Sampling::default(),
+            preset: None,
+            hue_range: None,
+            sat_range: None,
+            light_range: None,
+            palette_names: false,
+            mix_space: MixSpace::default(),
+            hue_path: HuePath::default(),
+            cam16: ViewingConditions::default(),
+            trim_fraction: 0.1,
+            kmeans_k: 2,
+            normalize_light: false,
+            layer_opacities: vec![0.5],
+            blend_mode: BlendMode::default(),
+            css_mix_space: CssMixSpace::default(),
+            best_representative: false,
+            chromaticity: None,
+            precision: 4,
+            scss: None,
+            only_failures: false,
+            strict: false,
+            quantize: None,
+            elevations: Vec::new(),
+            surface: RGB {
+                r: Ratio::from_u8(0x12),
+                g: Ratio::from_u8(0x12),
+                b: Ratio::from_u8(0x12),
+            },
+            css_color_format: CssColorFormat::Legacy,
+            cvd: None,
+            cvd_threshold: 10.0,
+            align_grid: false,
+            placeholder_style: PlaceholderStyle::Hidden,
+            normalize_luminance: false,
+            strip_steps: None,
+            easing: Easing::default(),
+            tournament: false,
+            preserve_brightness: false,
+            match_temperature: false,
+            preserve_chroma: false,
+            hue_vote: HueVote::default(),
+            achromatic: Achromatic::default(),
+            explain_diff: None,
+            gamut_coverage: false,
+            save_json: None,
+            stylesheet: None,
+            mixer_commands: Vec::new(),
+            #[cfg(feature = "wasm-plugins")]
+            plugin_dir: None,
+            #[cfg(feature = "serve")]
+            serve: None,
+            #[cfg(feature = "gif-output")]
+            gif: None,
+        }
+    }
+}
+
+/// A slug for a record id or mixer name made from `name`, its lowercase ASCII
+/// letters and digits with a `-` for every run of anything else. `palette`
+/// if nothing is left.
+pub fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "palette".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Whether `name` is a slug of lowercase ASCII letters, digits and `-`, as
+/// the names of mixers and palettes have to be.
+pub fn is_slug(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Fails with an error naming `name` unless it [`is_slug`].
+pub fn check_slug(name: &str) -> Result<(), String> {
+    if is_slug(name) {
+        Ok(())
+    } else {
+        Err(format!(
+            "the name `{}` is not a slug of a-z, 0-9 and -",
+            name
+        ))
+    }
+}
+
+/// Parses a `#rrggbb` or `#rgb` hex color, the leading `#` is optional.
+pub fn parse_hex(hex: &str) -> Result<RGB, String> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    let channels = match digits.len() {
+        _ if !digits.chars().all(|c| c.is_ascii_hexdigit()) => (None, None, None),
+        6 => (
+            channel(&digits[0..2]),
+            channel(&digits[2..4]),
+            channel(&digits[4..6]),
+        ),
+        3 => (
+            channel(&digits[0..1].repeat(2)),
+            channel(&digits[1..2].repeat(2)),
+            channel(&digits[2..3].repeat(2)),
+        ),
+        _ => (None, None, None),
+    };
+    match channels {
+        (Some(r), Some(g), Some(b)) => Ok(RGB {
+            r: Ratio::from_u8(r),
+            g: Ratio::from_u8(g),
+            b: Ratio::from_u8(b),
+        }),
+        _ => Err(format!("`{}` is not a hex color like #ff8800", hex)),
+    }
+}
+
+/// Parses a hex color with an optional alpha, `#rrggbbaa` and `#rgba` as
+/// well as the opaque forms of `parse_hex`. Returns the color and its alpha
+/// in `0.0..=1.0`.
+pub fn parse_hex_alpha(hex: &str) -> Result<(RGB, f32), String> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    let (color, alpha) = match digits.len() {
+        _ if !digits.chars().all(|c| c.is_ascii_hexdigit()) => {
+            return parse_hex(hex).map(|color| (color, 1.0))
+        }
+        8 => (&digits[..6], digits[6..].to_string()),
+        4 => (&digits[..3], digits[3..].repeat(2)),
+        _ => return parse_hex(hex).map(|color| (color, 1.0)),
+    };
+    let error = || format!("`{}` is not a hex color like #ff8800 or #ff880080", hex);
+    let color = parse_hex(color).map_err(|_| error())?;
+    let alpha = u8::from_str_radix(&alpha, 16).map_err(|_| error())?;
+    Ok((color, f32::from(alpha) / 255.0))
+}
+
+/// Parses a hex color like `parse_hex`, a CSS named color like `tomato` or
+/// an opaque CSS color function like `rgb(12 34 56)`.
+pub fn parse_color(color: &str) -> Result<RGB, String> {
+    if css_color::is_function(color) {
+        return match css_color::parse(color)? {
+            (color, alpha) if alpha >= 1.0 => Ok(color),
+            _ => Err(format!("`{}` must be opaque", color)),
+        };
+    }
+    match named::lookup(color) {
+        Some(named) => Ok(named),
+        None => parse_hex(color).map_err(|_| {
+            with_suggestion(
+                color,
+                format!(
+                    "`{}` is not a hex color like #ff8800 or a CSS name like tomato",
+                    color
+                ),
+            )
+        }),
+    }
+}
+
+/// A guess at the color meant by `color` that could not be parsed: the
+/// closest CSS name to a misspelled one, or hex with a digit too many or
+/// too few or with letters typed for the digits they look like.
+pub fn suggest(color: &str) -> Option<String> {
+    let color = color.trim();
+    if !color.is_empty() && color.chars().all(|c| c.is_ascii_alphabetic()) {
+        if let Some(name) = named::closest(color) {
+            return Some(name.to_string());
+        }
+    }
+    let digits = color.strip_prefix('#').unwrap_or(color);
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    let mut fixed: String = digits
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| match c {
+            'o' => '0',
+            'i' | 'l' => '1',
+            's' => '5',
+            c => c,
+        })
+        .collect();
+    match fixed.len() {
+        5 => fixed.push(fixed.as_bytes()[4] as char),
+        7 => {
+            fixed.pop();
+        }
+        _ => {}
+    }
+    parse_hex(&fixed).ok().map(|_| format!("#{}", fixed))
+}
+
+/// Appends the suggestion of `suggest` to `error`, if there is one.
+fn with_suggestion(color: &str, error: String) -> String {
+    match suggest(color) {
+        Some(suggestion) => format!("{}, did you mean {}?", error, suggestion),
+        None => error,
+    }
+}
+
+/// Parses a color with an optional alpha like `parse_hex_alpha`, a CSS named
+/// color, where `transparent` is transparent black, or a CSS color function
+/// like `hsl(200 50% 40% / 0.5)`.
+pub fn parse_color_alpha(color: &str) -> Result<(RGB, f32), String> {
+    if css_color::is_function(color) {
+        return css_color::parse(color);
+    }
+    if color.eq_ignore_ascii_case("transparent") {
+        return Ok((named::lookup("black").unwrap(), 0.0));
+    }
+    match named::lookup(color) {
+        Some(named) => Ok((named, 1.0)),
+        None => parse_hex_alpha(color).map_err(|_| {
+            with_suggestion(
+                color,
+                format!(
+                    "`{}` is not a hex color like #ff8800 or #ff880080 or a CSS name like tomato",
+                    color
+                ),
+            )
+        }),
+    }
+}
+
+/// A color of the `mix` command with its alpha and weight.
+#[derive(Debug, Clone, Copy)]
+pub struct WeightedColor {
+    pub color: RGB,
+    pub alpha: f32,
+    pub weight: f32,
+}
+
+/// Parses a color with an optional alpha and weight, like `#ff0000@3`,
+/// `#ff000080@3` or `tomato@3`. Colors without a weight get a weight of 1.0.
+pub fn parse_weighted_hex(color: &str) -> Result<WeightedColor, String> {
+    let (hex, weight) = match color.split_once('@') {
+        Some((hex, weight)) => {
+            let weight: f32 = weight
+                .parse()
+                .map_err(|_| format!("`{}` is not a weight like 3 or 0.5", weight))?;
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(format!("weight of `{}` must not be negative", color));
+            }
+            (hex, weight)
+        }
+        None => (color, 1.0),
+    };
+    let (color, alpha) = parse_color_alpha(hex)?;
+    Ok(WeightedColor {
+        color,
+        alpha,
+        weight,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex_rejects_signs() {
+        assert!(parse_hex("#+f+f+f").is_err());
+        assert!(parse_hex("+ff").is_err());
+        assert!(parse_hex_alpha("#ffffff+f").is_err());
+        assert!(parse_hex("#0f0f0f").is_ok());
+    }
+}
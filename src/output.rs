@@ -0,0 +1,272 @@
+//! Formatting of colors and metrics, the page of a run and its exports.
+
+use crate::options::{CssColorFormat, Options};
+use crate::Record;
+use css_colors::{Color, RGB};
+use std::fs;
+use std::io;
+use std::iter;
+
+/// Formats a color for the generated CSS in the `--css-color-format` syntax.
+pub fn css_color(color: RGB, options: &Options) -> String {
+    match options.css_color_format {
+        CssColorFormat::Legacy => color.to_css(),
+        CssColorFormat::ModernRgb => format!(
+            "rgb({} {} {})",
+            color.r.as_u8(),
+            color.g.as_u8(),
+            color.b.as_u8()
+        ),
+    }
+}
+
+/// Formats a color with `alpha` for the generated CSS, like `css_color`
+/// for opaque colors and `rgba()`, or `rgb()` with a slash, otherwise.
+pub fn css_color_alpha(color: RGB, alpha: f32, options: &Options) -> String {
+    if alpha >= 1.0 {
+        return css_color(color, options);
+    }
+    let alpha = (alpha * 1000.0).round() / 1000.0;
+    let (r, g, b) = (color.r.as_u8(), color.g.as_u8(), color.b.as_u8());
+    match options.css_color_format {
+        CssColorFormat::Legacy => format!("rgba({}, {}, {}, {})", r, g, b, alpha),
+        CssColorFormat::ModernRgb => format!("rgb({} {} {} / {})", r, g, b, alpha),
+    }
+}
+
+/// Formats a numeric metric with the `--precision` number of decimals, so
+/// that every emitted metric is rounded the same way.
+pub fn format_metric(value: f32, options: &Options) -> String {
+    format!("{:.*}", options.precision, value)
+}
+
+/// Formats a color as a `#rrggbb` hex string.
+pub fn to_hex(color: RGB) -> String {
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        color.r.as_u8(),
+        color.g.as_u8(),
+        color.b.as_u8()
+    )
+}
+
+/// Formats a color as `#rrggbb`, or as `#rrggbbaa` if `alpha` is below
+/// full opacity.
+pub fn to_hex_alpha(color: RGB, alpha: f32) -> String {
+    if alpha >= 1.0 {
+        to_hex(color)
+    } else {
+        format!("{}{:02x}", to_hex(color), (alpha * 255.0).round() as u8)
+    }
+}
+
+/// Escapes text for use in HTML content and single quoted attributes.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\'' => escaped.push_str("&#39;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// CSS rules overriding the swatch sizes of the stylesheet, if configured.
+pub fn swatch_css(options: &Options) -> String {
+    let rule = |selector: &str, size: &Option<String>| match size {
+        Some(size) => format!(
+            "{} {{
+    width: {};
+    height: {};
+}}\n",
+            selector, size, size
+        ),
+        None => String::new(),
+    };
+    rule(".input", &options.input_swatch_size) + &rule(".output", &options.output_swatch_size)
+}
+
+/// The CSS rule of the `--placeholder-style` class. It is generated rather
+/// than part of the stylesheet, so it also applies with `--stylesheet`.
+pub fn placeholder_css(options: &Options) -> String {
+    let style = options.placeholder_style;
+    format!(".{} {{\n{}}}\n", style.class(), style.declarations())
+}
+
+/// The default `index.css` of a run, laying out the records.
+pub const STYLESHEET: &str = include_str!("../res/index.css");
+
+/// The `index.css` of a run, read from `Options::stylesheet` if it is set.
+pub fn stylesheet(options: &Options) -> io::Result<String> {
+    match &options.stylesheet {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e))),
+        None => Ok(STYLESHEET.to_string()),
+    }
+}
+
+/// The `colors.css` of a run, coloring the swatches of every record.
+pub fn to_css(records: &[Record], options: &Options) -> String {
+    iter::once(swatch_css(options))
+        .chain(iter::once(placeholder_css(options)))
+        .chain(records.iter().map(|r| r.to_css(options)))
+        .collect()
+}
+
+/// The `index.html` of a run, linking `index.css` and `colors.css`
+/// regardless of `Options::css_name`.
+pub fn to_html(records: &[Record], options: &Options) -> String {
+    let slots = if options.align_grid {
+        records.iter().map(|r| r.input.len()).max().unwrap_or(0)
+    } else {
+        0
+    };
+    let content: String = records.iter().map(|r| r.to_html(slots, options)).collect();
+    format!("{}{}{}", html_head("colors.css"), content, HTML_TAIL)
+}
+
+/// The start of `index.html` linking the swatch colors at `css_name`, up to
+/// the first record.
+pub fn html_head(css_name: &str) -> String {
+    head("", css_name)
+}
+
+/// The start of a page linking the stylesheets at `prefix`.
+fn head(prefix: &str, css_name: &str) -> String {
+    format!(
+        "<html>
+ <head>
+<link rel='stylesheet' type='text/css' href='{0}index.css'>
+<link rel='stylesheet' type='text/css' href='{0}{1}'>
+</head>
+<body>
+",
+        escape_html(prefix),
+        escape_html(css_name)
+    )
+}
+
+/// The page of a single record for `--palette-pages`, kept in a directory
+/// below the report and linking its stylesheets at `css_name`.
+pub fn palette_page(record: &Record, css_name: &str, options: &Options) -> String {
+    format!(
+        "{}{}{}",
+        head("../", css_name),
+        record.to_html(0, options),
+        HTML_TAIL
+    )
+}
+
+/// The end of `index.html`, after the last record.
+pub const HTML_TAIL: &str = "
+</body>
+</html>";
+
+/// Renders every output as a Sass map keyed by `<strategy>-<record id>`.
+pub fn to_scss(records: &[Record]) -> String {
+    let entries: Vec<String> = records.iter().flat_map(scss_entries).collect();
+    format!("{}{}{}", SCSS_HEAD, entries.join(",\n"), SCSS_TAIL)
+}
+
+/// The start of the Sass map of `to_scss`.
+pub const SCSS_HEAD: &str = "$color-mix: (\n";
+
+/// The end of the Sass map of `to_scss`.
+pub const SCSS_TAIL: &str = "\n);\n";
+
+/// The entries of one record in the Sass map of `to_scss`, without the
+/// separating commas.
+pub fn scss_entries(record: &Record) -> impl Iterator<Item = String> + '_ {
+    record.outputs.iter().map(move |(slug, color)| {
+        format!(
+            "    \"{}-{}\": {}",
+            slug,
+            record.id,
+            to_hex_alpha(*color, record.alpha())
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::PlaceholderStyle;
+    use crate::{generate, mixer};
+    use css_colors::rgb;
+
+    #[test]
+    fn metrics_round_to_the_precision() {
+        let options = Options {
+            precision: 2,
+            ..Options::default()
+        };
+        assert_eq!(format_metric(1.23456, &options), "1.23");
+        assert_eq!(format_metric(0.999, &options), "1.00");
+    }
+
+    #[test]
+    fn scss_is_a_map_keyed_by_strategy_and_record() {
+        let record = generate::compute_record(
+            "3-2".to_string(),
+            vec![rgb(255, 0, 0), rgb(0, 0, 255)],
+            &mixer::registry(&Options::default()),
+        );
+        let scss = to_scss(std::slice::from_ref(&record));
+        assert!(scss.starts_with("$color-mix: ("));
+        assert!(scss.trim_end().ends_with(");"));
+        for (slug, _) in record.outputs.iter() {
+            assert!(scss.contains(&format!("\"{}-3-2\": #", slug)));
+        }
+    }
+
+    #[test]
+    fn modern_rgb_css_separates_channels_with_spaces() {
+        let options = Options {
+            css_color_format: CssColorFormat::ModernRgb,
+            ..Options::default()
+        };
+        let record = generate::compute_record(
+            "1".to_string(),
+            vec![rgb(255, 128, 0), rgb(0, 0, 255)],
+            &mixer::registry(&options),
+        );
+        let css = to_css(std::slice::from_ref(&record), &options);
+        assert!(css.contains("rgb(255 128 0)"));
+        assert!(!css.contains("rgb(255, 128, 0)"));
+        assert_eq!(
+            css_color_alpha(rgb(255, 128, 0), 0.5, &options),
+            "rgb(255 128 0 / 0.5)"
+        );
+    }
+
+    #[test]
+    fn placeholder_class_is_in_the_generated_css_and_markup() {
+        let mixers = mixer::registry(&Options::default());
+        let records = [
+            generate::compute_record("1".to_string(), vec![rgb(255, 0, 0); 2], &mixers),
+            generate::compute_record("2".to_string(), vec![rgb(0, 0, 255); 4], &mixers),
+        ];
+        for style in [
+            PlaceholderStyle::Hidden,
+            PlaceholderStyle::Checkerboard,
+            PlaceholderStyle::Outline,
+        ] {
+            let options = Options {
+                align_grid: true,
+                placeholder_style: style,
+                ..Options::default()
+            };
+            let class = style.class();
+            assert!(!STYLESHEET.contains(class));
+            let css = to_css(&records, &options);
+            assert!(css.contains(&format!(".{} {{\n{}}}", class, style.declarations())));
+            let html = to_html(&records, &options);
+            assert_eq!(html.matches(&format!("placeholder {}", class)).count(), 2);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Paint mixing with the Kubelka-Munk model over three pigment spectra.
+//!
+//! Every color is a paint made of a red, a green and a blue pigment, in the
+//! amounts of its linear channels. The pigments reflect over the ten bands
+//! of [`spectral`] and add up to a flat white, and the blue
+//! pigment reflects far into the green like real blue paints do. So where
+//! blue and yellow paints both reflect, in the green, survives their mix.
+//! The projection back is the least squares inverse of the pigments, so
+//! every paint projects back to the color it was made of.
+
+use crate::convert::{linear_to_rgb, rgb_to_linear};
+use crate::spectral::{self, Spectrum, BANDS};
+use css_colors::RGB;
+use std::sync::OnceLock;
+
+const BLUE: Spectrum = [0.90, 0.95, 1.00, 0.90, 0.55, 0.20, 0.05, 0.00, 0.00, 0.00];
+const RED: Spectrum = [0.10, 0.05, 0.00, 0.00, 0.00, 0.00, 0.10, 0.50, 0.90, 1.00];
+
+/// Reflectance of the darkest paint, about that of a black pigment. Black
+/// paint is strong but mixed with white still gives a gray.
+const DARKEST: f32 = 0.02;
+
+/// The red, green and blue pigments, the green one whatever the other two
+/// leave of white.
+fn pigments() -> [Spectrum; 3] {
+    let mut green = [0.0; BANDS];
+    for (band, g) in green.iter_mut().enumerate() {
+        *g = 1.0 - RED[band] - BLUE[band];
+    }
+    [RED, green, BLUE]
+}
+
+/// The paint of `color`.
+pub fn to_spectrum(color: RGB) -> Spectrum {
+    let amounts = rgb_to_linear(color);
+    let mut spectrum = [0.0; BANDS];
+    for (pigment, amount) in pigments().iter().zip(amounts) {
+        for (s, p) in spectrum.iter_mut().zip(pigment) {
+            *s += p * amount;
+        }
+    }
+    spectrum
+}
+
+/// The linear-light response of every channel to every band. With P the
+/// bands x pigments matrix, the response is `(P^T P)^-1 P^T`.
+fn response() -> &'static [[f32; BANDS]; 3] {
+    static RESPONSE: OnceLock<[[f32; BANDS]; 3]> = OnceLock::new();
+    RESPONSE.get_or_init(|| {
+        let pigments = pigments();
+        let dot = |a: &Spectrum, b: &Spectrum| -> f64 {
+            a.iter()
+                .zip(b)
+                .map(|(x, y)| f64::from(*x) * f64::from(*y))
+                .sum()
+        };
+        let gram: Vec<Vec<f64>> = pigments
+            .iter()
+            .map(|a| pigments.iter().map(|b| dot(a, b)).collect())
+            .collect();
+        let cofactor = |i: usize, j: usize| {
+            let (r0, r1) = ((i + 1) % 3, (i + 2) % 3);
+            let (c0, c1) = ((j + 1) % 3, (j + 2) % 3);
+            gram[r0][c0] * gram[r1][c1] - gram[r0][c1] * gram[r1][c0]
+        };
+        let det: f64 = (0..3).map(|j| gram[0][j] * cofactor(0, j)).sum();
+
+        let mut response = [[0.0; BANDS]; 3];
+        for (channel, row) in response.iter_mut().enumerate() {
+            for (band, r) in row.iter_mut().enumerate() {
+                // The gram matrix is symmetric, so its inverse is the
+                // cofactor matrix over the determinant.
+                *r = (0..3)
+                    .map(|j| cofactor(channel, j) / det * f64::from(pigments[j][band]))
+                    .sum::<f64>() as f32;
+            }
+        }
+        response
+    })
+}
+
+/// Projects a paint back to sRGB, clamping out of gamut results.
+pub fn from_spectrum(spectrum: &Spectrum) -> RGB {
+    let response = response();
+    linear_to_rgb(response.map(|row| row.iter().zip(spectrum).map(|(r, s)| r * s).sum()))
+}
+
+/// Mixes paints, averaging the `K/S` of every band weighted by `weights`.
+/// `None` if the total weight is not positive.
+pub fn mix(paints: &[Spectrum], weights: &[f32]) -> Option<Spectrum> {
+    spectral::mix_with_floor(paints, weights, DARKEST)
+}
@@ -0,0 +1,100 @@
+//! Snapping colors to the nearest color of a restricted palette, to see
+//! what the mixes become on a limited display or in a fixed set of colors.
+
+use crate::delta_e::delta_e;
+use crate::options::parse_color;
+use css_colors::{Ratio, RGB};
+use std::fs;
+use std::str::FromStr;
+
+/// The 16 colors of the standard ANSI terminal palette, as xterm shows
+/// them.
+const ANSI16: [u32; 16] = [
+    0x000000, 0x800000, 0x008000, 0x808000, 0x000080, 0x800080, 0x008080, 0xc0c0c0, 0x808080,
+    0xff0000, 0x00ff00, 0xffff00, 0x0000ff, 0xff00ff, 0x00ffff, 0xffffff,
+];
+
+/// A palette the outputs can be snapped to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Palette {
+    /// The 216 web-safe colors, every channel one of 00, 33, 66, 99, cc and
+    /// ff.
+    WebSafe,
+    /// The 16 ANSI terminal colors.
+    Ansi16,
+    /// Colors read from a file.
+    Custom(Vec<RGB>),
+}
+
+impl Palette {
+    /// Every color of the palette.
+    pub fn colors(&self) -> Vec<RGB> {
+        let rgb = |r: u8, g: u8, b: u8| RGB {
+            r: Ratio::from_u8(r),
+            g: Ratio::from_u8(g),
+            b: Ratio::from_u8(b),
+        };
+        match self {
+            Palette::WebSafe => {
+                let levels = (0..6u8).map(|l| l * 0x33);
+                levels
+                    .clone()
+                    .flat_map(|r| {
+                        let levels = levels.clone();
+                        levels
+                            .clone()
+                            .flat_map(move |g| levels.clone().map(move |b| rgb(r, g, b)))
+                    })
+                    .collect()
+            }
+            Palette::Ansi16 => ANSI16
+                .iter()
+                .map(|c| rgb((c >> 16) as u8, (c >> 8) as u8, *c as u8))
+                .collect(),
+            Palette::Custom(colors) => colors.clone(),
+        }
+    }
+
+    /// The color of the palette with the smallest ΔE2000 to `color`, the
+    /// first one on ties. `color` itself if the palette is empty.
+    pub fn nearest(&self, color: RGB) -> RGB {
+        let mut best = (color, f32::INFINITY);
+        for candidate in self.colors() {
+            let distance = delta_e(color, candidate);
+            if distance < best.1 {
+                best = (candidate, distance);
+            }
+        }
+        best.0
+    }
+}
+
+/// Reads a palette file with one hex or CSS named color per line. Blank
+/// lines and lines starting with `//` are skipped.
+pub fn load(path: &str) -> Result<Vec<RGB>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let colors: Vec<RGB> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        .enumerate()
+        .map(|(n, line)| parse_color(line).map_err(|e| format!("{}: color {}: {}", path, n + 1, e)))
+        .collect::<Result<_, _>>()?;
+    if colors.is_empty() {
+        return Err(format!("{}: the palette has no colors", path));
+    }
+    Ok(colors)
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    /// `web-safe`, `ansi16` or the path of a palette file, see [`load`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "web-safe" => Ok(Palette::WebSafe),
+            "ansi16" => Ok(Palette::Ansi16),
+            path => load(path).map(Palette::Custom),
+        }
+    }
+}
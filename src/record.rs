@@ -0,0 +1,665 @@
+//! The results of mixing one palette, and their rendering to CSS and HTML.
+
+use crate::alpha;
+use crate::blend;
+use crate::chromaticity;
+use crate::convert::rgb_to_hsl;
+use crate::css_mix;
+use crate::cvd;
+use crate::delta_e;
+use crate::options::parse_hex;
+use crate::options::{DeltaBaseline, Options};
+use crate::output::{css_color, css_color_alpha, escape_html, format_metric, to_hex};
+use css_colors::{HSL, RGB};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// The inputs of one palette together with the result of every mixer.
+///
+/// Serializes with its colors as `#rrggbb` hex strings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "RecordData", try_from = "RecordData")]
+pub struct Record {
+    /// Identifier used in CSS classes, `<input count>-<round>` for generated
+    /// records.
+    pub id: String,
+    pub input: Vec<RGB>,
+    /// The weight of every input the outputs were mixed with, 1.0 each for
+    /// generated records.
+    pub weights: Vec<f32>,
+    /// The alpha of every input, 1.0 each for opaque inputs. The outputs are
+    /// mixed premultiplied and share the alpha of `Record::alpha`.
+    pub alphas: Vec<f32>,
+    /// Every output color, keyed by the slug of the mixer producing it, in
+    /// registry order.
+    pub outputs: Vec<(String, RGB)>,
+    /// The mixers that failed and fell back to black.
+    pub failures: Vec<Failure>,
+    /// Human readable name of a curated palette.
+    pub label: Option<String>,
+    pub tags: Vec<String>,
+    /// Where the inputs came from.
+    pub source: Option<String>,
+    /// The interpolation strip of every mixer from the first input to the
+    /// second, keyed by slug, if the record has two inputs and strips were
+    /// requested.
+    pub strips: Vec<(String, Vec<RGB>)>,
+}
+
+/// A mixer that could not compute a record's output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Failure {
+    pub mixer: String,
+    /// The `ComputeError`, as displayed.
+    pub error: String,
+}
+
+/// The serialized form of a `Record`.
+#[derive(Serialize, Deserialize)]
+struct RecordData {
+    id: String,
+    input: Vec<String>,
+    /// Left out if every input has a weight of 1.0.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    weights: Vec<f32>,
+    /// Left out if every input is opaque.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    alphas: Vec<f32>,
+    outputs: Vec<OutputData>,
+    #[serde(default)]
+    failures: Vec<Failure>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    strips: Vec<StripData>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OutputData {
+    slug: String,
+    color: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StripData {
+    slug: String,
+    colors: Vec<String>,
+}
+
+impl From<Record> for RecordData {
+    fn from(record: Record) -> Self {
+        let (weighted, translucent) = (record.is_weighted(), record.is_translucent());
+        let weights = if weighted { record.weights } else { Vec::new() };
+        let alphas = if translucent {
+            record.alphas
+        } else {
+            Vec::new()
+        };
+        RecordData {
+            id: record.id,
+            input: record.input.into_iter().map(to_hex).collect(),
+            weights,
+            alphas,
+            outputs: record
+                .outputs
+                .into_iter()
+                .map(|(slug, color)| OutputData {
+                    slug,
+                    color: to_hex(color),
+                })
+                .collect(),
+            failures: record.failures,
+            label: record.label,
+            tags: record.tags,
+            source: record.source,
+            strips: record
+                .strips
+                .into_iter()
+                .map(|(slug, colors)| StripData {
+                    slug,
+                    colors: colors.into_iter().map(to_hex).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<RecordData> for Record {
+    type Error = String;
+
+    fn try_from(data: RecordData) -> Result<Self, Self::Error> {
+        if data.input.is_empty() {
+            return Err(format!("record `{}` has no inputs", data.id));
+        }
+        let weights = if data.weights.is_empty() {
+            vec![1.0; data.input.len()]
+        } else if data.weights.len() == data.input.len() {
+            data.weights
+        } else {
+            return Err(format!(
+                "record `{}` has {} weights for {} inputs",
+                data.id,
+                data.weights.len(),
+                data.input.len()
+            ));
+        };
+        let alphas = if data.alphas.is_empty() {
+            vec![1.0; data.input.len()]
+        } else if data.alphas.len() == data.input.len() {
+            if let Some(alpha) = data.alphas.iter().find(|a| !(0.0..=1.0).contains(*a)) {
+                return Err(format!(
+                    "record `{}` has an alpha of {} outside of 0.0..=1.0",
+                    data.id, alpha
+                ));
+            }
+            data.alphas
+        } else {
+            return Err(format!(
+                "record `{}` has {} alphas for {} inputs",
+                data.id,
+                data.alphas.len(),
+                data.input.len()
+            ));
+        };
+        Ok(Record {
+            id: data.id,
+            input: data
+                .input
+                .iter()
+                .map(|c| parse_hex(c))
+                .collect::<Result<_, _>>()?,
+            weights,
+            alphas,
+            outputs: data
+                .outputs
+                .into_iter()
+                .map(|o| Ok((o.slug, parse_hex(&o.color)?)))
+                .collect::<Result<_, String>>()?,
+            failures: data.failures,
+            label: data.label,
+            tags: data.tags,
+            source: data.source,
+            strips: data
+                .strips
+                .into_iter()
+                .map(|strip| {
+                    let colors = strip.colors.iter().map(|c| parse_hex(c));
+                    Ok((strip.slug, colors.collect::<Result<_, _>>()?))
+                })
+                .collect::<Result<_, String>>()?,
+        })
+    }
+}
+
+impl Record {
+    /// Whether the inputs have differing weights, rather than 1.0 each.
+    pub fn is_weighted(&self) -> bool {
+        self.weights.iter().any(|w| *w != 1.0)
+    }
+
+    /// Whether any input is not fully opaque.
+    pub fn is_translucent(&self) -> bool {
+        self.alphas.iter().any(|a| *a < 1.0)
+    }
+
+    /// The alpha of every output, the weighted mean alpha of the inputs.
+    pub fn alpha(&self) -> f32 {
+        alpha::mixed_alpha(&self.weights, &self.alphas)
+    }
+
+    /// The alpha of input `n`.
+    fn input_alpha(&self, n: usize) -> f32 {
+        self.alphas.get(n).copied().unwrap_or(1.0)
+    }
+
+    /// The CSS rules coloring this record's swatches.
+    pub fn to_css(&self, options: &Options) -> String {
+        let input: String = self
+            .input
+            .iter()
+            .enumerate()
+            .map(|(n, c)| {
+                swatch_css(
+                    &format!("input-{}", n),
+                    &self.id,
+                    *c,
+                    self.input_alpha(n),
+                    options,
+                )
+            })
+            .collect();
+        let outputs: String = self
+            .outputs
+            .iter()
+            .map(|(slug, color)| {
+                let rule = swatch_css(slug, &self.id, *color, self.alpha(), options);
+                if slug == "css-color-mix" {
+                    let expression = css_mix::expression(
+                        options.css_mix_space,
+                        options.hue_path,
+                        &self.input,
+                        &self.weights,
+                    );
+                    format!("/* {} */\n{}", expression, rule)
+                } else {
+                    rule
+                }
+            })
+            .collect();
+        let elevations: String = self
+            .outputs
+            .iter()
+            .flat_map(|(slug, color)| {
+                options.elevations.iter().map(move |elevation| {
+                    format!(
+                        ".record-{} .{} .elevation-{} {{
+    background-color: {};
+}}\n",
+                        &self.id,
+                        slug,
+                        elevation,
+                        css_color(
+                            blend::on_surface(*color, options.surface, *elevation),
+                            options,
+                        ),
+                    )
+                })
+            })
+            .collect();
+        let strips: String = self
+            .strips
+            .iter()
+            .flat_map(|(slug, colors)| {
+                colors.iter().enumerate().map(move |(k, color)| {
+                    format!(
+                        ".record-{} .{} .step-{} {{
+    background-color: {};
+}}\n",
+                        &self.id,
+                        slug,
+                        k,
+                        css_color(*color, options),
+                    )
+                })
+            })
+            .collect();
+        let quantized: String = match &options.quantize {
+            Some(palette) => self
+                .outputs
+                .iter()
+                .map(|(slug, color)| {
+                    format!(
+                        ".record-{} .{} .quantized {{
+    background-color: {};
+}}\n",
+                        &self.id,
+                        slug,
+                        css_color(palette.nearest(*color), options),
+                    )
+                })
+                .collect(),
+            None => String::new(),
+        };
+        vec![input, outputs, elevations, strips, quantized]
+            .into_iter()
+            .collect()
+    }
+
+    /// The interpolation strip of the mixer with `slug`, if it has one.
+    fn strip(&self, slug: &str) -> Option<&[RGB]> {
+        self.strips
+            .iter()
+            .find(|(s, _)| *s == slug)
+            .map(|(_, colors)| colors.as_slice())
+    }
+
+    /// The output of the mixer with `slug`, if it was run.
+    pub fn output(&self, slug: &str) -> Option<RGB> {
+        self.outputs
+            .iter()
+            .find(|(s, _)| *s == slug)
+            .map(|(_, c)| *c)
+    }
+
+    /// The mixer whose output has the smallest total ΔE2000 to all of the
+    /// inputs. The first mixer wins on ties.
+    pub fn best_representative(&self) -> &str {
+        let mut best = ("", f32::INFINITY);
+        for (slug, output) in self.outputs.iter() {
+            let error = representation_error(*output, &self.input);
+            if error < best.1 {
+                best = (slug, error);
+            }
+        }
+        best.0
+    }
+
+    /// The `rgb-avg` output, or the first output if that mixer was not run.
+    fn rgb_avg_or_first(&self) -> RGB {
+        self.output("rgb-avg")
+            .or_else(|| self.outputs.first().map(|(_, c)| *c))
+            .unwrap_or(self.input[0])
+    }
+
+    /// The label, tags and source of the record, if it has any.
+    fn metadata_label(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(label) = &self.label {
+            parts.push(format!("<span class='label'>{}</span>", escape_html(label)));
+        }
+        for tag in &self.tags {
+            parts.push(format!("<span class='tag'>{}</span>", escape_html(tag)));
+        }
+        if let Some(source) = &self.source {
+            parts.push(format!(
+                "<span class='source'>{}</span>",
+                escape_html(source)
+            ));
+        }
+        if parts.is_empty() {
+            return String::new();
+        }
+        format!("    <div class='record-meta'>{}</div>\n", parts.concat())
+    }
+
+    /// Report of how distinguishable the outputs remain under `--cvd`.
+    fn cvd_label(&self, options: &Options) -> String {
+        let cvd = match options.cvd {
+            Some(cvd) => cvd,
+            None => return String::new(),
+        };
+        let colors: Vec<RGB> = self.outputs.iter().map(|(_, c)| *c).collect();
+        match cvd::min_pairwise_delta_e(&colors, cvd) {
+            Some(delta) => {
+                let class = if delta < options.cvd_threshold {
+                    "cvd cvd-low"
+                } else {
+                    "cvd"
+                };
+                format!(
+                    "    <div class='{}'>{:?} min &Delta;E {}</div>\n",
+                    class,
+                    cvd,
+                    format_metric(delta, options)
+                )
+            }
+            None => String::new(),
+        }
+    }
+
+    /// Share of the sRGB chromaticity gamut spanned by the inputs, if
+    /// `--gamut-coverage` is enabled.
+    fn gamut_coverage_label(&self, options: &Options) -> String {
+        if !options.gamut_coverage {
+            return String::new();
+        }
+        format!(
+            "    <div class='gamut-coverage'>gamut coverage {}%</div>\n",
+            format_metric(chromaticity::gamut_coverage(&self.input) * 100.0, options)
+        )
+    }
+
+    /// Label for an output swatch, the ΔE2000 from the chosen baseline if
+    /// `--annotate-delta` is enabled.
+    fn delta_label(&self, output: RGB, options: &Options) -> String {
+        let baseline = match options.annotate_delta {
+            Some(DeltaBaseline::FirstInput) => self.input[0],
+            Some(DeltaBaseline::RgbAvg) => self.rgb_avg_or_first(),
+            None => return String::new(),
+        };
+        format!(
+            "<span class='delta'>&Delta;E {}</span>",
+            format_metric(delta_e::delta_e(baseline, output), options)
+        )
+    }
+
+    /// Renders the record, padding the inputs with placeholders up to
+    /// `slots` entries.
+    pub fn to_html(&self, slots: usize, options: &Options) -> String {
+        let input: String = self
+            .input
+            .iter()
+            .enumerate()
+            .map(|(n, _)| {
+                let translucent = translucent_class(self.input_alpha(n));
+                match self.weights.get(n) {
+                    Some(weight) if self.is_weighted() => format!(
+                        "<div class='input input-{}{}'><span class='weight'>&times;{}</span></div>\n",
+                        n, translucent, weight
+                    ),
+                    _ => format!("<div class='input input-{}{}'></div>\n", n, translucent),
+                }
+            })
+            .chain((self.input.len()..slots).map(|_| {
+                format!(
+                    "<div class='input placeholder {}'></div>\n",
+                    options.placeholder_style.class()
+                )
+            }))
+            .collect();
+        let heading = if options.palette_names {
+            format!("<h3 class='palette-name'>{}</h3>\n", palette_name(self))
+        } else {
+            String::new()
+        };
+        let best = if options.best_representative {
+            Some(self.best_representative())
+        } else {
+            None
+        };
+        let elevations = if options.elevations.is_empty() {
+            String::new()
+        } else {
+            let swatches: String = options
+                .elevations
+                .iter()
+                .map(|e| {
+                    format!(
+                        "<div class='elevation elevation-{}' title='{}dp'></div>",
+                        e, e
+                    )
+                })
+                .collect();
+            format!("<div class='elevations'>{}</div>", swatches)
+        };
+        let outputs: String = self
+            .outputs
+            .iter()
+            .map(|(slug, color)| {
+                let (class, label) = if best == Some(slug.as_str()) {
+                    (
+                        " best",
+                        "<span class='best-label'>best representative</span>",
+                    )
+                } else {
+                    ("", "")
+                };
+                let (strip_class, steps) = match self.strip(slug) {
+                    Some(colors) => (
+                        " strip",
+                        (0..colors.len())
+                            .map(|k| format!("<div class='step step-{}'></div>", k))
+                            .collect(),
+                    ),
+                    None => ("", String::new()),
+                };
+                let quantized = match &options.quantize {
+                    Some(palette) => format!(
+                        "<div class='quantized' title='{}'></div>",
+                        to_hex(palette.nearest(*color))
+                    ),
+                    None => String::new(),
+                };
+                format!(
+                    "        <div class='output {}{}{}{}'>{}{}{}{}{}</div>\n",
+                    slug,
+                    translucent_class(self.alpha()),
+                    strip_class,
+                    class,
+                    steps,
+                    self.delta_label(*color, options),
+                    label,
+                    elevations,
+                    quantized,
+                )
+            })
+            .collect();
+        format!(
+            "{}<div class='record record-{}'>
+{}    <div class='inputs'>
+    {}
+    </div>
+    <div class='outputs'>
+{}    </div>
+{}{}</div>\n",
+            heading,
+            self.id,
+            self.metadata_label(),
+            input,
+            outputs,
+            self.cvd_label(options),
+            self.gamut_coverage_label(options),
+        )
+    }
+}
+
+/// The class marking a swatch of a translucent color, if `alpha` is one.
+fn translucent_class(alpha: f32) -> &'static str {
+    if alpha < 1.0 {
+        " translucent"
+    } else {
+        ""
+    }
+}
+
+/// The CSS rule coloring the swatch with `class` in record `id`. Translucent
+/// colors are also set as `--swatch-color`, which the stylesheet paints over
+/// a checkerboard.
+fn swatch_css(class: &str, id: &str, color: RGB, alpha: f32, options: &Options) -> String {
+    let css = css_color_alpha(color, alpha, options);
+    if alpha < 1.0 {
+        format!(
+            ".record-{} .{} {{
+    background-color: {};
+    --swatch-color: {};
+}}\n",
+            id, class, css, css
+        )
+    } else {
+        format!(
+            ".record-{} .{} {{
+    background-color: {};
+}}\n",
+            id, class, css
+        )
+    }
+}
+
+/// How badly `output` stands in for the inputs, as the sum of the ΔE2000
+/// from it to every input.
+pub fn representation_error(output: RGB, input: &[RGB]) -> f32 {
+    input.iter().map(|c| delta_e::delta_e(output, *c)).sum()
+}
+
+/// A human readable name like "muted teal" for a record, derived from the
+/// hue and lightness of its `rgb-avg` output.
+pub fn palette_name(record: &Record) -> String {
+    let HSL { h, s, l } = rgb_to_hsl(record.rgb_avg_or_first());
+    let (s, l) = (s.as_percentage(), l.as_percentage());
+
+    if s < 10 || !(8..=95).contains(&l) {
+        let gray = match l {
+            0..=7 => "black",
+            8..=30 => "charcoal",
+            31..=70 => "gray",
+            71..=95 => "silver",
+            _ => "white",
+        };
+        return gray.to_string();
+    }
+
+    let hue = match h.degrees() {
+        0..=14 | 345..=360 => "red",
+        15..=44 => "orange",
+        45..=69 => "yellow",
+        70..=164 => "green",
+        165..=194 => "teal",
+        195..=254 => "blue",
+        255..=289 => "purple",
+        290..=329 => "magenta",
+        _ => "pink",
+    };
+    let descriptor = if l < 25 {
+        "dark "
+    } else if l > 75 {
+        "pale "
+    } else if s < 40 {
+        "muted "
+    } else if s > 80 {
+        "vivid "
+    } else {
+        ""
+    };
+    format!("{}{}", descriptor, hue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::compute_record;
+    use crate::mixer;
+    use css_colors::rgb;
+
+    fn record(input: Vec<RGB>) -> Record {
+        compute_record(
+            "test".to_string(),
+            input,
+            &mixer::registry(&Options::default()),
+        )
+    }
+
+    #[test]
+    fn delta_label_of_the_baseline_is_zero() {
+        let record = record(vec![rgb(200, 40, 90), rgb(20, 140, 60)]);
+        let options = Options {
+            annotate_delta: Some(DeltaBaseline::FirstInput),
+            ..Options::default()
+        };
+        let label = record.delta_label(record.input[0], &options);
+        let value: f32 = label
+            .trim_start_matches("<span class='delta'>&Delta;E ")
+            .trim_end_matches("</span>")
+            .parse()
+            .unwrap();
+        assert!(value.abs() < 1e-3, "{}", label);
+    }
+
+    #[test]
+    fn palette_name_of_bright_red_is_red() {
+        let record = record(vec![rgb(255, 0, 0), rgb(230, 20, 10)]);
+        assert!(palette_name(&record).contains("red"));
+    }
+
+    #[test]
+    fn best_representative_picks_the_first_of_tied_mixers() {
+        let record = record(vec![rgb(90, 90, 90), rgb(90, 90, 90)]);
+        assert_eq!(record.best_representative(), record.outputs[0].0);
+    }
+
+    #[test]
+    fn best_representative_has_the_least_representation_error() {
+        let record = record(vec![rgb(200, 40, 40), rgb(205, 45, 38), rgb(198, 38, 44)]);
+        let best = record.best_representative();
+        let error = |slug: &str| {
+            let output = record.outputs.iter().find(|o| o.0 == slug).unwrap().1;
+            representation_error(output, &record.input)
+        };
+        for (slug, _) in record.outputs.iter() {
+            assert!(error(best) <= error(slug), "{} beats {}", slug, best);
+        }
+    }
+}
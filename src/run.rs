@@ -0,0 +1,357 @@
+//! A whole run, its records and metadata, as saved to and loaded from JSON,
+//! and [`run`] driving the pipeline from a [`RunConfig`].
+//!
+//! ```no_run
+//! use color_mix::run::{run, RunConfig, Sink};
+//! use std::fs::File;
+//!
+//! let config = RunConfig::new()
+//!     .input_len(2..=4)
+//!     .rounds(3)
+//!     .seed(42)
+//!     .sink(Sink::Html(Box::new(File::create("index.html")?)))
+//!     .sink(Sink::Css(Box::new(File::create("colors.css")?)));
+//! let result = run(config)?;
+//! assert_eq!(result.records.len(), 9);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::generate;
+use crate::mixer::{self, Mixer};
+use crate::{chromaticity, output, Options, Record};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How a run was produced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetadata {
+    /// Version of color-mix that produced the run.
+    pub version: String,
+    /// Seconds since the Unix epoch when the run was produced.
+    pub created: u64,
+    pub max_len: usize,
+    pub rounds: usize,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Slugs of the mixers that were run, in output order.
+    pub mixers: Vec<String>,
+}
+
+/// The records of a run together with its metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Run {
+    pub metadata: RunMetadata,
+    pub records: Vec<Record>,
+}
+
+impl RunMetadata {
+    /// The metadata of a run with `mixers` and `options`, created now.
+    pub fn new(mixers: &[Box<dyn Mixer>], options: &Options) -> RunMetadata {
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        RunMetadata {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            created,
+            max_len: options.max_len,
+            rounds: options.rounds,
+            seed: options.seed,
+            mixers: mixers.iter().map(|m| m.slug().to_string()).collect(),
+        }
+    }
+
+    /// The UTC day the run was created on, like `2024-03-09`.
+    pub fn date(&self) -> String {
+        // Days since the epoch to a proleptic Gregorian date, after
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = (self.created / 86_400) as i64 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+
+    /// Expands a file name template, replacing `{seed}` with the seed of the
+    /// run, or `unseeded`, and `{date}` with `date`. See [`check_template`].
+    pub fn file_name(&self, template: &str) -> String {
+        let seed = self.seed.map_or("unseeded".to_string(), |s| s.to_string());
+        template
+            .replace("{seed}", &seed)
+            .replace("{date}", &self.date())
+    }
+}
+
+/// Checks that `template` only uses the placeholders `RunMetadata::file_name`
+/// expands.
+pub fn check_template(template: &str) -> Result<(), String> {
+    let rest = template.replace("{seed}", "").replace("{date}", "");
+    if rest.contains(['{', '}']) {
+        return Err(format!(
+            "unknown placeholder in `{}`, expected {{seed}} or {{date}}",
+            template
+        ));
+    }
+    if rest.is_empty() {
+        return Err("file name must not be empty".to_string());
+    }
+    Ok(())
+}
+
+impl Run {
+    pub fn new(records: Vec<Record>, mixers: &[Box<dyn Mixer>], options: &Options) -> Run {
+        Run {
+            metadata: RunMetadata::new(mixers, options),
+            records,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Run> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Where `run` or a `RunWriter` writes one of the outputs of a run.
+pub enum Sink {
+    /// The `colors.css` of the page.
+    Css(Box<dyn Write>),
+    /// The `index.html` of the page.
+    Html(Box<dyn Write>),
+    Scss(Box<dyn Write>),
+    Chromaticity(Box<dyn Write>),
+    /// The `Run` as JSON.
+    Json(Box<dyn Write>),
+    /// A page per record in this directory, named after the record id and
+    /// linking the `colors.css` of the page in the directory above.
+    Pages(PathBuf),
+}
+
+/// Writes the outputs of a run to its sinks record by record, so that no
+/// output has to be held in memory as a whole.
+pub struct RunWriter<'a> {
+    sinks: Vec<Sink>,
+    options: &'a Options,
+    /// Inputs every record is padded to in the HTML.
+    slots: usize,
+    /// File name of the page's `colors.css`, for `Sink::Pages`.
+    css_name: String,
+    written: usize,
+    scss_entries: usize,
+}
+
+impl<'a> RunWriter<'a> {
+    /// Writes the start of every sink. `max_input_len` is the most inputs a
+    /// record will have, for `--align-grid`.
+    pub fn new(
+        mut sinks: Vec<Sink>,
+        metadata: &RunMetadata,
+        max_input_len: usize,
+        options: &'a Options,
+    ) -> io::Result<RunWriter<'a>> {
+        let css_name = metadata.file_name(&options.css_name);
+        for sink in &mut sinks {
+            match sink {
+                Sink::Css(w) => {
+                    w.write_all(output::swatch_css(options).as_bytes())?;
+                    w.write_all(output::placeholder_css(options).as_bytes())?;
+                }
+                Sink::Html(w) => w.write_all(output::html_head(&css_name).as_bytes())?,
+                Sink::Scss(w) => w.write_all(output::SCSS_HEAD.as_bytes())?,
+                Sink::Chromaticity(w) => w.write_all(chromaticity::svg_header().as_bytes())?,
+                Sink::Json(w) => {
+                    let metadata = serde_json::to_string_pretty(metadata)?;
+                    write!(
+                        w,
+                        "{{\n  \"metadata\": {},\n  \"records\": [\n",
+                        metadata.replace('\n', "\n  ")
+                    )?
+                }
+                Sink::Pages(dir) => fs::create_dir_all(dir)?,
+            }
+        }
+        let slots = if options.align_grid { max_input_len } else { 0 };
+        Ok(RunWriter {
+            sinks,
+            options,
+            slots,
+            css_name,
+            written: 0,
+            scss_entries: 0,
+        })
+    }
+
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        let first = self.written == 0;
+        for sink in &mut self.sinks {
+            match sink {
+                Sink::Css(w) => w.write_all(record.to_css(self.options).as_bytes())?,
+                Sink::Html(w) => {
+                    w.write_all(record.to_html(self.slots, self.options).as_bytes())?
+                }
+                Sink::Scss(w) => {
+                    for entry in output::scss_entries(record) {
+                        if self.scss_entries > 0 {
+                            w.write_all(b",\n")?;
+                        }
+                        w.write_all(entry.as_bytes())?;
+                        self.scss_entries += 1;
+                    }
+                }
+                Sink::Chromaticity(w) => {
+                    w.write_all(chromaticity::svg_record(record).as_bytes())?
+                }
+                Sink::Json(w) => {
+                    if !first {
+                        w.write_all(b",\n")?;
+                    }
+                    let record = serde_json::to_string_pretty(record)?;
+                    write!(w, "    {}", record.replace('\n', "\n    "))?;
+                }
+                Sink::Pages(dir) => {
+                    let page = output::palette_page(record, &self.css_name, self.options);
+                    fs::write(dir.join(format!("{}.html", record.id)), page)?
+                }
+            }
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Writes the end of every sink and flushes them.
+    pub fn finish(mut self) -> io::Result<()> {
+        for sink in &mut self.sinks {
+            let w = match sink {
+                Sink::Css(w) => w,
+                Sink::Html(w) => {
+                    w.write_all(output::HTML_TAIL.as_bytes())?;
+                    w
+                }
+                Sink::Scss(w) => {
+                    w.write_all(output::SCSS_TAIL.as_bytes())?;
+                    w
+                }
+                Sink::Chromaticity(w) => {
+                    w.write_all(chromaticity::SVG_FOOTER.as_bytes())?;
+                    w
+                }
+                Sink::Json(w) => {
+                    w.write_all(b"\n  ]\n}")?;
+                    w
+                }
+                Sink::Pages(_) => continue,
+            };
+            w.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds the configuration of a run for `run`.
+pub struct RunConfig {
+    options: Options,
+    mixers: Vec<Box<dyn Mixer>>,
+    sinks: Vec<Sink>,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        RunConfig::new()
+    }
+}
+
+impl RunConfig {
+    /// A run with the default `Options` and all registered mixers.
+    pub fn new() -> RunConfig {
+        RunConfig {
+            options: Options::default(),
+            mixers: Vec::new(),
+            sinks: Vec::new(),
+        }
+    }
+
+    /// Replaces the options, including those set by other methods so far.
+    pub fn options(mut self, options: Options) -> RunConfig {
+        self.options = options;
+        self
+    }
+
+    /// The numbers of inputs records are generated with.
+    pub fn input_len(mut self, lengths: RangeInclusive<usize>) -> RunConfig {
+        self.options.min_len = *lengths.start();
+        self.options.max_len = *lengths.end();
+        self
+    }
+
+    /// Records per input count.
+    pub fn rounds(mut self, rounds: usize) -> RunConfig {
+        self.options.rounds = rounds;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> RunConfig {
+        self.options.seed = Some(seed);
+        self
+    }
+
+    /// Adds a mixer. Without any, the mixers of `mixer::registry` are run.
+    pub fn mixer(mut self, mixer: Box<dyn Mixer>) -> RunConfig {
+        self.mixers.push(mixer);
+        self
+    }
+
+    /// Adds an output to write once the records are generated.
+    pub fn sink(mut self, sink: Sink) -> RunConfig {
+        self.sinks.push(sink);
+        self
+    }
+}
+
+/// The records of a finished run and its metadata, or why it failed.
+pub type RunResult = io::Result<Run>;
+
+/// Generates the records configured by `config` and writes them to its
+/// sinks. Runs without a seed are seeded randomly, the seed used is in the
+/// metadata of the result.
+pub fn run(config: RunConfig) -> RunResult {
+    let RunConfig {
+        mut options,
+        mut mixers,
+        sinks,
+    } = config;
+    if options.min_len == 0 || options.min_len > options.max_len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "invalid input lengths {}..={}",
+                options.min_len, options.max_len
+            ),
+        ));
+    }
+    options.seed = Some(options.seed.unwrap_or_else(rand::random));
+    if mixers.is_empty() {
+        mixers = mixer::registry(&options);
+    }
+
+    let metadata = RunMetadata::new(&mixers, &options);
+    let (generated, max_input_len) = generate::sized_records(&options, &mixers)?;
+    let mut writer = RunWriter::new(sinks, &metadata, max_input_len, &options)?;
+    let mut records = Vec::new();
+    for record in generated {
+        writer.write(&record)?;
+        records.push(record);
+    }
+    writer.finish()?;
+    Ok(Run { metadata, records })
+}
@@ -0,0 +1,90 @@
+//! A minimal HTTP server for previewing a run, behind the `serve` feature.
+
+use std::io;
+
+fn content_type(name: &str) -> &'static str {
+    match name.rsplit('.').next() {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Serves the generated `files` (name and content) from memory until the
+/// process is killed. `/` is served as `index.html`.
+pub fn serve(addr: &str, files: &[(&str, String)]) -> io::Result<()> {
+    let server = tiny_http::Server::http(addr).map_err(io::Error::other)?;
+    println!("Serving preview on http://{}/", addr);
+
+    for request in server.incoming_requests() {
+        let path = request.url().split('?').next().unwrap_or("");
+        let path = match path.trim_start_matches('/') {
+            "" => "index.html",
+            path => path,
+        };
+
+        let result = match files.iter().find(|(name, _)| *name == path) {
+            Some((name, content)) => {
+                let header =
+                    tiny_http::Header::from_bytes(&b"Content-Type"[..], content_type(name))
+                        .expect("static header is valid");
+                request
+                    .respond(tiny_http::Response::from_string(content.as_str()).with_header(header))
+            }
+            None => {
+                request.respond(tiny_http::Response::from_string("not found").with_status_code(404))
+            }
+        };
+        if let Err(e) = result {
+            log::warn!("failed to respond: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::{thread, time::Duration};
+
+    #[test]
+    fn root_is_served_as_html() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|l| l.local_addr())
+            .unwrap()
+            .port();
+        let addr = format!("127.0.0.1:{}", port);
+        let served = addr.clone();
+        // The server never returns, the thread ends with the test process.
+        thread::spawn(move || {
+            serve(&served, &[("index.html", "<html></html>".to_string())]).unwrap()
+        });
+
+        let mut stream = None;
+        for _ in 0..50 {
+            match TcpStream::connect(&addr) {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                }
+                Err(_) => thread::sleep(Duration::from_millis(20)),
+            }
+        }
+        let mut stream = stream.expect("server did not start");
+        write!(
+            stream,
+            "GET / HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            addr
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.contains("text/html"));
+        assert!(response.ends_with("<html></html>"));
+    }
+}
@@ -0,0 +1,204 @@
+//! Color spaces that colors can be averaged in, and the weighted average
+//! shared by all averaging strategies.
+
+use crate::convert::{lab_to_rgb, linear_to_rgb, oklab_to_rgb, rgb_to_lab, rgb_to_linear};
+use crate::convert::{
+    lms_to_rgb, rgb_to_lms, rgb_to_oklab, rgb_to_xyz, rgb_to_ycbcr, xyz_to_rgb, ycbcr_to_rgb, Lab,
+    Oklab,
+};
+use css_colors::{Ratio, RGB};
+
+/// A space whose components can be averaged linearly. Implementors are unit
+/// structs, passed by value to pick the space.
+pub trait ColorSpace {
+    fn to_components(color: RGB) -> [f32; 3];
+    fn from_components(components: [f32; 3]) -> RGB;
+}
+
+/// Gamma encoded sRGB bytes in `0.0..=255.0`. Averages are truncated, which
+/// keeps the results identical to the original integer `rgb_avg`.
+#[derive(Debug, Clone, Copy)]
+pub struct SrgbSpace;
+
+impl ColorSpace for SrgbSpace {
+    fn to_components(color: RGB) -> [f32; 3] {
+        [
+            f32::from(color.r.as_u8()),
+            f32::from(color.g.as_u8()),
+            f32::from(color.b.as_u8()),
+        ]
+    }
+
+    fn from_components([r, g, b]: [f32; 3]) -> RGB {
+        let channel = |value: f32| Ratio::from_u8(value.clamp(0.0, 255.0) as u8);
+        RGB {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+        }
+    }
+}
+
+/// Linear-light sRGB.
+#[derive(Debug, Clone, Copy)]
+pub struct LinearSpace;
+
+impl ColorSpace for LinearSpace {
+    fn to_components(color: RGB) -> [f32; 3] {
+        rgb_to_linear(color)
+    }
+
+    fn from_components(components: [f32; 3]) -> RGB {
+        linear_to_rgb(components)
+    }
+}
+
+/// BT.709 Y'CbCr as 8-bit studio range codes.
+#[derive(Debug, Clone, Copy)]
+pub struct YcbcrSpace;
+
+impl ColorSpace for YcbcrSpace {
+    fn to_components(color: RGB) -> [f32; 3] {
+        rgb_to_ycbcr(color)
+    }
+
+    fn from_components(components: [f32; 3]) -> RGB {
+        ycbcr_to_rgb(components)
+    }
+}
+
+/// CIE XYZ (D65).
+#[derive(Debug, Clone, Copy)]
+pub struct XyzSpace;
+
+impl ColorSpace for XyzSpace {
+    fn to_components(color: RGB) -> [f32; 3] {
+        rgb_to_xyz(color)
+    }
+
+    fn from_components(components: [f32; 3]) -> RGB {
+        xyz_to_rgb(components)
+    }
+}
+
+/// LMS cone responses.
+#[derive(Debug, Clone, Copy)]
+pub struct LmsSpace;
+
+impl ColorSpace for LmsSpace {
+    fn to_components(color: RGB) -> [f32; 3] {
+        rgb_to_lms(color)
+    }
+
+    fn from_components(components: [f32; 3]) -> RGB {
+        lms_to_rgb(components)
+    }
+}
+
+/// CIELAB (D65).
+#[derive(Debug, Clone, Copy)]
+pub struct LabSpace;
+
+impl ColorSpace for LabSpace {
+    fn to_components(color: RGB) -> [f32; 3] {
+        let Lab { l, a, b } = rgb_to_lab(color);
+        [l, a, b]
+    }
+
+    fn from_components([l, a, b]: [f32; 3]) -> RGB {
+        lab_to_rgb(Lab { l, a, b })
+    }
+}
+
+/// OKLab.
+#[derive(Debug, Clone, Copy)]
+pub struct OklabSpace;
+
+impl ColorSpace for OklabSpace {
+    fn to_components(color: RGB) -> [f32; 3] {
+        let Oklab { l, a, b } = rgb_to_oklab(color);
+        [l, a, b]
+    }
+
+    fn from_components([l, a, b]: [f32; 3]) -> RGB {
+        oklab_to_rgb(Oklab { l, a, b })
+    }
+}
+
+/// Converts every input into `space`, takes the weighted average of the
+/// components and converts the result back. `weights` pairs up with
+/// `inputs`, missing weights count as zero. `None` if there is nothing to
+/// average, i.e. the total weight is not positive.
+pub fn blend_many_weighted<S: ColorSpace>(
+    inputs: &[RGB],
+    weights: &[f32],
+    _space: S,
+) -> Option<RGB> {
+    let total: f32 = weights.iter().take(inputs.len()).sum();
+    if total <= 0.0 || !total.is_finite() {
+        return None;
+    }
+
+    let mut sum = [0.0f32; 3];
+    for (color, weight) in inputs.iter().zip(weights) {
+        let components = S::to_components(*color);
+        for (s, c) in sum.iter_mut().zip(components.iter()) {
+            *s += c * weight;
+        }
+    }
+    Some(S::from_components(sum.map(|s| s / total)))
+}
+
+/// Converts every input into `space` and reduces each component on its own
+/// with `reduce`, which gets the `(component, weight)` of every input.
+/// `None` if `reduce` gives up on a component.
+pub fn reduce_per_component<S: ColorSpace>(
+    inputs: &[RGB],
+    weights: &[f32],
+    _space: S,
+    reduce: impl Fn(&[(f32, f32)]) -> Option<f32>,
+) -> Option<RGB> {
+    let components: Vec<[f32; 3]> = inputs.iter().map(|c| S::to_components(*c)).collect();
+    let mut reduced = [0.0f32; 3];
+    for (i, r) in reduced.iter_mut().enumerate() {
+        let samples: Vec<(f32, f32)> = components
+            .iter()
+            .zip(weights)
+            .map(|(c, w)| (c[i], *w))
+            .collect();
+        *r = reduce(&samples)?;
+    }
+    Some(S::from_components(reduced))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{linear_avg, rgb_avg};
+    use css_colors::rgb;
+
+    fn input() -> [RGB; 3] {
+        [rgb(255, 0, 0), rgb(0, 0, 255), rgb(10, 21, 3)]
+    }
+
+    #[test]
+    fn srgb_space_reproduces_the_integer_rgb_avg() {
+        let blended = blend_many_weighted(&input(), &[1.0; 3], SrgbSpace).unwrap();
+        // The channel sums 265, 21 and 258 divided by 3, truncated.
+        assert_eq!(blended, rgb(88, 7, 86));
+        assert_eq!(blended, rgb_avg(input()).unwrap());
+    }
+
+    #[test]
+    fn linear_space_reproduces_the_linear_avg() {
+        let blended = blend_many_weighted(&input(), &[1.0; 3], LinearSpace).unwrap();
+        let mut mean = [0.0; 3];
+        for color in input().iter() {
+            for (m, c) in mean.iter_mut().zip(rgb_to_linear(*color)) {
+                *m += c / 3.0;
+            }
+        }
+        assert_eq!(blended, linear_to_rgb(mean));
+        assert_eq!(blended, linear_avg(input()).unwrap());
+    }
+}
@@ -0,0 +1,190 @@
+//! Spectral mixing: colors are upsampled to reflectance spectra, mixed as
+//! paints per wavelength band, and projected back to sRGB.
+//!
+//! Spectra are built from the seven basis spectra of Smits, "An RGB to
+//! Spectrum Conversion for Reflectances" (1999), over ten bands from 380nm
+//! to 720nm. The projection back is the least squares inverse of that
+//! construction, so every spectrum projects back to the color it was
+//! upsampled from. Mixing clamps the reflectances of the basis above 1.0,
+//! which can shift saturated primaries by a few levels.
+
+use crate::convert::{linear_to_rgb, rgb_to_linear};
+use css_colors::RGB;
+use std::sync::OnceLock;
+
+/// Number of wavelength bands of a spectrum.
+pub const BANDS: usize = 10;
+
+/// A reflectance per wavelength band, from short to long wavelengths.
+pub type Spectrum = [f32; BANDS];
+
+const WHITE: Spectrum = [
+    1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+];
+const CYAN: Spectrum = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+];
+const MAGENTA: Spectrum = [
+    1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+];
+const YELLOW: Spectrum = [
+    0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const RED: Spectrum = [
+    0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const GREEN: Spectrum = [
+    0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+];
+const BLUE: Spectrum = [
+    1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+/// The basis spectra with the linear-light color each one stands for.
+const BASIS: [(Spectrum, [f32; 3]); 7] = [
+    (WHITE, [1.0, 1.0, 1.0]),
+    (CYAN, [0.0, 1.0, 1.0]),
+    (MAGENTA, [1.0, 0.0, 1.0]),
+    (YELLOW, [1.0, 1.0, 0.0]),
+    (RED, [1.0, 0.0, 0.0]),
+    (GREEN, [0.0, 1.0, 0.0]),
+    (BLUE, [0.0, 0.0, 1.0]),
+];
+
+/// Smallest reflectance of a band while mixing, a band reflecting nothing
+/// would absorb infinitely.
+const MIN_REFLECTANCE: f32 = 1e-3;
+
+/// Upsamples a color to a reflectance spectrum with Smits' method: the
+/// common part of the linear channels is white, the rest is made of the
+/// secondary and primary spectra.
+pub fn to_spectrum(color: RGB) -> Spectrum {
+    let [r, g, b] = rgb_to_linear(color);
+    let mut spectrum = [0.0; BANDS];
+    let mut add = |basis: &Spectrum, amount: f32| {
+        for (s, b) in spectrum.iter_mut().zip(basis) {
+            *s += b * amount;
+        }
+    };
+    if r <= g && r <= b {
+        add(&WHITE, r);
+        if g <= b {
+            add(&CYAN, g - r);
+            add(&BLUE, b - g);
+        } else {
+            add(&CYAN, b - r);
+            add(&GREEN, g - b);
+        }
+    } else if g <= r && g <= b {
+        add(&WHITE, g);
+        if r <= b {
+            add(&MAGENTA, r - g);
+            add(&BLUE, b - r);
+        } else {
+            add(&MAGENTA, b - g);
+            add(&RED, r - b);
+        }
+    } else {
+        add(&WHITE, b);
+        if r <= g {
+            add(&YELLOW, r - b);
+            add(&GREEN, g - r);
+        } else {
+            add(&YELLOW, g - b);
+            add(&RED, r - g);
+        }
+    }
+    spectrum
+}
+
+/// The linear-light response of every channel to every band, the minimum
+/// norm solution mapping each basis spectrum to its color.
+fn response() -> &'static [[f32; BANDS]; 3] {
+    static RESPONSE: OnceLock<[[f32; BANDS]; 3]> = OnceLock::new();
+    RESPONSE.get_or_init(|| {
+        // With S the bands x basis matrix and C the channels x basis colors,
+        // the response is C (S^T S)^-1 S^T.
+        let n = BASIS.len();
+        let mut gram = vec![vec![0.0f64; 2 * n]; n];
+        for i in 0..n {
+            for j in 0..n {
+                gram[i][j] = (0..BANDS)
+                    .map(|k| f64::from(BASIS[i].0[k]) * f64::from(BASIS[j].0[k]))
+                    .sum();
+            }
+            gram[i][n + i] = 1.0;
+        }
+        // Gauss-Jordan elimination with partial pivoting, leaving the
+        // inverse in the right half.
+        for col in 0..n {
+            let pivot = (col..n)
+                .max_by(|a, b| gram[*a][col].abs().total_cmp(&gram[*b][col].abs()))
+                .unwrap_or(col);
+            gram.swap(col, pivot);
+            let p = gram[col][col];
+            for v in gram[col].iter_mut() {
+                *v /= p;
+            }
+            let pivot_row = gram[col].clone();
+            for (row, values) in gram.iter_mut().enumerate() {
+                if row != col {
+                    let factor = values[col];
+                    for (v, p) in values.iter_mut().zip(&pivot_row) {
+                        *v -= factor * p;
+                    }
+                }
+            }
+        }
+
+        let mut response = [[0.0; BANDS]; 3];
+        for (channel, row) in response.iter_mut().enumerate() {
+            for (band, r) in row.iter_mut().enumerate() {
+                *r = (0..n)
+                    .map(|i| {
+                        let weight: f64 = (0..n)
+                            .map(|j| gram[i][n + j] * f64::from(BASIS[j].0[band]))
+                            .sum();
+                        f64::from(BASIS[i].1[channel]) * weight
+                    })
+                    .sum::<f64>() as f32;
+            }
+        }
+        response
+    })
+}
+
+/// Projects a reflectance spectrum back to sRGB, clamping out of gamut
+/// results.
+pub fn from_spectrum(spectrum: &Spectrum) -> RGB {
+    let response = response();
+    linear_to_rgb(response.map(|row| row.iter().zip(spectrum).map(|(r, s)| r * s).sum()))
+}
+
+/// Mixes spectra as paints, averaging the single-constant Kubelka-Munk
+/// `K/S` of every band weighted by `weights`. `None` if the total weight is
+/// not positive.
+pub fn mix(spectra: &[Spectrum], weights: &[f32]) -> Option<Spectrum> {
+    mix_with_floor(spectra, weights, MIN_REFLECTANCE)
+}
+
+/// [`mix`] with every reflectance lifted to `floor + (1 - floor) * r` while
+/// mixing and lowered back after, so a band reflecting nothing does not
+/// absorb infinitely and identical spectra mix to themselves.
+pub fn mix_with_floor(spectra: &[Spectrum], weights: &[f32], floor: f32) -> Option<Spectrum> {
+    let total: f32 = weights.iter().take(spectra.len()).sum();
+    if total <= 0.0 || !total.is_finite() {
+        return None;
+    }
+    let mut ks = [0.0f32; BANDS];
+    for (spectrum, weight) in spectra.iter().zip(weights) {
+        for (k, r) in ks.iter_mut().zip(spectrum) {
+            let r = floor + (1.0 - floor) * r.clamp(0.0, 1.0);
+            *k += (1.0 - r).powi(2) / (2.0 * r) * weight;
+        }
+    }
+    Some(ks.map(|k| {
+        let k = k / total;
+        let r = 1.0 + k - (k * k + 2.0 * k).sqrt();
+        ((r - floor) / (1.0 - floor)).clamp(0.0, 1.0)
+    }))
+}
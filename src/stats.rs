@@ -0,0 +1,168 @@
+//! Robust statistics over weighted samples, used by the per-channel
+//! strategies, and weighted k-means clustering.
+
+/// The weighted median of `(value, weight)` samples. Where the cumulative
+/// weight reaches exactly half of the total, the median is the mean of the
+/// values on either side, so equal weights give the usual median. `None` if
+/// the total weight is not positive.
+pub fn weighted_median(samples: &[(f32, f32)]) -> Option<f32> {
+    let mut samples = samples.to_vec();
+    samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let total: f32 = samples.iter().map(|(_, w)| w).sum();
+    if total <= 0.0 || !total.is_finite() {
+        return None;
+    }
+
+    let half = total / 2.0;
+    let mut cumulative = 0.0;
+    for (i, (value, weight)) in samples.iter().enumerate() {
+        cumulative += weight;
+        if cumulative > half {
+            return Some(*value);
+        }
+        if cumulative == half {
+            let next = samples[i + 1..]
+                .iter()
+                .find(|(_, w)| *w > 0.0)
+                .map_or(*value, |(v, _)| *v);
+            return Some((value + next) / 2.0);
+        }
+    }
+    samples.last().map(|(v, _)| *v)
+}
+
+/// The weighted mean of `(value, weight)` samples after discarding `trim`
+/// of the total weight from both the low and the high end, cutting through
+/// samples where needed. `trim` is clamped to `0.0..0.5`, at which the
+/// trimmed mean approaches the median. `None` if the total weight is not
+/// positive.
+pub fn trimmed_mean(samples: &[(f32, f32)], trim: f32) -> Option<f32> {
+    let mut samples = samples.to_vec();
+    samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let total: f32 = samples.iter().map(|(_, w)| w).sum();
+    if total <= 0.0 || !total.is_finite() {
+        return None;
+    }
+    let trim = trim.clamp(0.0, 0.5);
+    if trim >= 0.5 {
+        return weighted_median(&samples);
+    }
+
+    let (low, high) = (total * trim, total * (1.0 - trim));
+    let (mut sum, mut kept) = (0.0, 0.0);
+    let mut start = 0.0;
+    for (value, weight) in samples {
+        let end = start + weight;
+        let overlap = end.min(high) - start.max(low);
+        if overlap > 0.0 {
+            sum += value * overlap;
+            kept += overlap;
+        }
+        start = end;
+    }
+    if kept > 0.0 {
+        Some(sum / kept)
+    } else {
+        None
+    }
+}
+
+/// The weighted geometric mean of non-negative `(value, weight)` samples,
+/// `exp` of the weighted mean of the logarithms. Any zero of positive
+/// weight makes it zero. `None` if the total weight is not positive.
+pub fn geometric_mean(samples: &[(f32, f32)]) -> Option<f32> {
+    let total: f32 = samples.iter().map(|(_, w)| w).sum();
+    if total <= 0.0 || !total.is_finite() {
+        return None;
+    }
+    let mut log_sum = 0.0;
+    for (value, weight) in samples.iter().filter(|(_, w)| *w > 0.0) {
+        if *value <= 0.0 {
+            return Some(0.0);
+        }
+        log_sum += value.ln() * weight;
+    }
+    Some((log_sum / total).exp())
+}
+
+/// Squared euclidean distance of two points.
+fn distance2(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Index of the centroid nearest to `point`, the first one on ties.
+fn nearest(centroids: &[[f32; 3]], point: [f32; 3]) -> usize {
+    let mut best = (0, f32::INFINITY);
+    for (i, centroid) in centroids.iter().enumerate() {
+        let distance = distance2(*centroid, point);
+        if distance < best.1 {
+            best = (i, distance);
+        }
+    }
+    best.0
+}
+
+/// Clusters weighted `(point, weight)` samples into at most `k` clusters
+/// with Lloyd's k-means, returning the centroid and total weight of every
+/// cluster. Deterministic: the first centroid is the heaviest sample, every
+/// further one the sample furthest from the centroids so far, weighted by
+/// its weight, and seeding stops early once every sample is a centroid.
+pub fn kmeans(samples: &[([f32; 3], f32)], k: usize) -> Vec<([f32; 3], f32)> {
+    let mut centroids: Vec<[f32; 3]> = Vec::new();
+    while centroids.len() < k {
+        let mut best = (None, 0.0);
+        for (point, weight) in samples {
+            let score = if centroids.is_empty() {
+                *weight
+            } else {
+                weight * distance2(centroids[nearest(&centroids, *point)], *point)
+            };
+            if best.0.is_none() || score > best.1 {
+                best = (Some(*point), score);
+            }
+        }
+        match best {
+            (Some(point), score) if centroids.is_empty() || score > 0.0 => centroids.push(point),
+            _ => break,
+        }
+    }
+
+    let mut assignment: Vec<usize> = Vec::new();
+    for _ in 0..32 {
+        let next: Vec<usize> = samples
+            .iter()
+            .map(|(point, _)| nearest(&centroids, *point))
+            .collect();
+        if next == assignment {
+            break;
+        }
+        assignment = next;
+        for (i, centroid) in centroids.iter_mut().enumerate() {
+            let mut sum = [0.0f32; 3];
+            let mut total = 0.0;
+            for ((point, weight), _) in samples.iter().zip(&assignment).filter(|(_, a)| **a == i) {
+                for (s, c) in sum.iter_mut().zip(point) {
+                    *s += c * weight;
+                }
+                total += weight;
+            }
+            if total > 0.0 {
+                *centroid = sum.map(|s| s / total);
+            }
+        }
+    }
+
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, centroid)| {
+            let weight = samples
+                .iter()
+                .zip(&assignment)
+                .filter(|(_, a)| **a == i)
+                .map(|((_, w), _)| w)
+                .sum();
+            (*centroid, weight)
+        })
+        .collect()
+}
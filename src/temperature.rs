@@ -0,0 +1,89 @@
+//! Correlated color temperature of colors and white balancing a color from
+//! one temperature to another.
+
+use crate::convert::{lms_to_xyz, rgb_to_xy, rgb_to_xyz, xyz_to_lms, xyz_to_rgb};
+use css_colors::RGB;
+
+/// The range of temperatures in kelvin the Planckian locus approximation
+/// of [`planckian_xy`] covers.
+const CCT_RANGE: (f32, f32) = (1667.0, 25000.0);
+
+/// Below this relative luminance a color is too dark to have a temperature.
+const MIN_LUMINANCE: f32 = 1e-4;
+
+/// The correlated color temperature of `color` in kelvin by McCamy's
+/// formula, clamped to `1667..=25000` K. Only meaningful for colors near
+/// white, saturated colors get a temperature at either end. `None` for
+/// black.
+pub fn cct(color: RGB) -> Option<f32> {
+    if rgb_to_xyz(color)[1] < MIN_LUMINANCE {
+        return None;
+    }
+    let [x, y] = rgb_to_xy(color);
+    let n = (x - 0.3320) / (0.1858 - y);
+    let cct = 449.0 * n.powi(3) + 3525.0 * n.powi(2) + 6823.3 * n + 5520.33;
+    if cct.is_finite() {
+        Some(cct.clamp(CCT_RANGE.0, CCT_RANGE.1))
+    } else {
+        None
+    }
+}
+
+/// The mean temperature of `input` weighted by `weights`, averaged in
+/// mireds, which are closer to perceptually even steps than kelvin. Inputs
+/// without a temperature are left out, `None` if no input has one.
+pub fn mean_cct(input: &[RGB], weights: &[f32]) -> Option<f32> {
+    let (mireds, total) = input
+        .iter()
+        .zip(weights)
+        .filter_map(|(c, w)| cct(*c).map(|t| (1e6 / t, *w)))
+        .fold((0.0, 0.0), |(sum, total), (mired, w)| {
+            (sum + mired * w, total + w)
+        });
+    if total > 0.0 {
+        Some(1e6 / (mireds / total))
+    } else {
+        None
+    }
+}
+
+/// The xy chromaticity of a black body at `cct` kelvin, by the cubic spline
+/// of Kim et al.
+pub fn planckian_xy(cct: f32) -> [f32; 2] {
+    let t = cct.clamp(CCT_RANGE.0, CCT_RANGE.1);
+    let (t2, t3) = (t * t, t * t * t);
+    let x = if t <= 4000.0 {
+        -0.266_123_9e9 / t3 - 0.234_358_9e6 / t2 + 0.877_695_6e3 / t + 0.179_910
+    } else {
+        -3.025_846_9e9 / t3 + 2.107_038e6 / t2 + 0.222_634_7e3 / t + 0.240_390
+    };
+    let (x2, x3) = (x * x, x * x * x);
+    let y = if t <= 2222.0 {
+        -1.106_381_4 * x3 - 1.348_110_2 * x2 + 2.185_558_3 * x - 0.202_196_83
+    } else if t <= 4000.0 {
+        -0.954_947_6 * x3 - 1.374_185_9 * x2 + 2.091_37 * x - 0.167_488_67
+    } else {
+        3.081_758 * x3 - 5.873_387 * x2 + 3.751_13 * x - 0.370_014_83
+    };
+    [x, y]
+}
+
+/// The LMS cone responses of the white of a black body at `cct` kelvin.
+fn white_lms(cct: f32) -> [f32; 3] {
+    let [x, y] = planckian_xy(cct);
+    xyz_to_lms([x / y, 1.0, (1.0 - x - y) / y])
+}
+
+/// White balances `color` from its own temperature to `target` kelvin with
+/// a von Kries adaptation of the cone responses, keeping its luminance
+/// roughly. Colors without a temperature are kept.
+pub fn with_cct(color: RGB, target: f32) -> RGB {
+    let source = match cct(color) {
+        Some(source) => source,
+        None => return color,
+    };
+    let (from, to) = (white_lms(source), white_lms(target));
+    let lms = xyz_to_lms(rgb_to_xyz(color));
+    let adapted = [0, 1, 2].map(|i| lms[i] * to[i] / from[i]);
+    xyz_to_rgb(lms_to_xyz(adapted))
+}
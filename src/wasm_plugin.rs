@@ -0,0 +1,157 @@
+//! Mixers loaded from WebAssembly modules, to try out algorithms without
+//! forking the crate.
+//!
+//! Every `.wasm` file in the plugin directory is a mixer named after its
+//! file stem, which has to be a lowercase slug like `my-mix`. A module
+//! exports its `memory` and two functions:
+//!
+//! - `alloc(len: i32) -> i32` returns the offset of `len` free bytes in
+//!   `memory`,
+//! - `mix(ptr: i32, count: i32) -> i32` mixes the `count` inputs written to
+//!   `ptr` and returns the mix as `0xRRGGBB`, or a negative number if it
+//!   failed.
+//!
+//! Every input is four little endian `f32`: the gamma encoded sRGB channels
+//! in `0.0..=1.0` and its weight. Modules cannot import anything, and every
+//! call runs out of fuel after a fixed budget instead of hanging the run.
+
+use crate::mixer::Mixer;
+use crate::options::check_slug;
+use crate::ComputeError;
+use css_colors::{Ratio, RGB};
+use std::convert::TryFrom;
+use std::fs;
+use std::path::{Path, PathBuf};
+use wasmi::{Config, Engine, Linker, Module, Store};
+
+/// Fuel of one call, roughly the number of instructions it may execute.
+const FUEL: u64 = 10_000_000;
+
+/// Bytes of one input in the memory of a module.
+const INPUT_SIZE: usize = 16;
+
+/// A mixer implemented by a WebAssembly module.
+pub struct WasmMixer {
+    name: String,
+    path: PathBuf,
+    engine: Engine,
+    module: Module,
+}
+
+impl WasmMixer {
+    /// Compiles the module at `path`.
+    pub fn load(path: &Path) -> Result<WasmMixer, String> {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        check_slug(&name).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let wasm = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module =
+            Module::new(&engine, wasm).map_err(|e| format!("{}: {}", path.display(), e))?;
+        for export in ["memory", "alloc", "mix"] {
+            if !module.exports().any(|e| e.name() == export) {
+                return Err(format!("{}: does not export `{}`", path.display(), export));
+            }
+        }
+        Ok(WasmMixer {
+            name,
+            path: path.to_path_buf(),
+            engine,
+            module,
+        })
+    }
+
+    /// Runs `mix` of a fresh instance of the module on the inputs.
+    fn call(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, String> {
+        let mut store = Store::new(&self.engine, ());
+        store.set_fuel(FUEL).map_err(|e| e.to_string())?;
+        let instance = Linker::new(&self.engine)
+            .instantiate_and_start(&mut store, &self.module)
+            .map_err(|e| e.to_string())?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or("`memory` is not a memory")?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|e| e.to_string())?;
+        let mix = instance
+            .get_typed_func::<(i32, i32), i32>(&store, "mix")
+            .map_err(|e| e.to_string())?;
+
+        let bytes: Vec<u8> = input
+            .iter()
+            .zip(weights)
+            .flat_map(|(color, weight)| {
+                [
+                    color.r.as_f32(),
+                    color.g.as_f32(),
+                    color.b.as_f32(),
+                    *weight,
+                ]
+            })
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let len = i32::try_from(input.len() * INPUT_SIZE).map_err(|e| e.to_string())?;
+        let ptr = alloc.call(&mut store, len).map_err(|e| e.to_string())?;
+        let offset = usize::try_from(ptr).map_err(|_| "`alloc` returned a negative offset")?;
+        memory
+            .write(&mut store, offset, &bytes)
+            .map_err(|e| e.to_string())?;
+        let mixed = mix
+            .call(&mut store, (ptr, input.len() as i32))
+            .map_err(|e| e.to_string())?;
+        if !(0..=0xff_ffff).contains(&mixed) {
+            return Err(format!("`mix` returned {}", mixed));
+        }
+        let channel = |shift: i32| Ratio::from_u8((mixed >> shift) as u8);
+        Ok(RGB {
+            r: channel(16),
+            g: channel(8),
+            b: channel(0),
+        })
+    }
+}
+
+impl Mixer for WasmMixer {
+    fn slug(&self) -> &str {
+        &self.name
+    }
+
+    fn describe(&self) -> String {
+        format!("mixed by the plugin {}", self.path.display())
+    }
+
+    fn mix_weighted(&self, input: &[RGB], weights: &[f32]) -> Result<RGB, ComputeError> {
+        self.call(input, weights).map_err(ComputeError::Plugin)
+    }
+}
+
+/// Loads every `.wasm` module in `dir`, in file name order. Modules that do
+/// not load are left out with a warning.
+pub fn load_dir(dir: &Path) -> Vec<WasmMixer> {
+    let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+            .collect(),
+        Err(e) => {
+            log::warn!("cannot read plugins from {}: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+    paths.sort();
+    paths
+        .iter()
+        .filter_map(|path| match WasmMixer::load(path) {
+            Ok(mixer) => Some(mixer),
+            Err(e) => {
+                log::warn!("skipping plugin {}", e);
+                None
+            }
+        })
+        .collect()
+}